@@ -151,6 +151,10 @@ function setupEventListeners() {
     if (changeSettingsBtn) changeSettingsBtn.addEventListener('click', () => app.change_settings());
     if (newSessionBtn) newSessionBtn.addEventListener('click', () => app.new_session());
 
+    // Pause screen
+    const resumeBtn = document.getElementById('resume-btn');
+    if (resumeBtn) resumeBtn.addEventListener('click', () => app.resume());
+
     // Back buttons
     const backToWelcome = document.getElementById('back-to-welcome');
     const backToLanguage = document.getElementById('back-to-language');
@@ -169,10 +173,11 @@ function handleTypingInput(event) {
     console.log('Handling typing input:', typedText);
     
     // Get current sentence length to prevent over-typing
-    const currentSentence = app.current_sentence;
-    if (typedText.length > currentSentence.length) {
+    const sentenceUnits = splitGraphemes(app.current_sentence);
+    const typedUnits = splitGraphemes(typedText);
+    if (typedUnits.length > sentenceUnits.length) {
         // Limit input to sentence length and process the trimmed text
-        const trimmedText = typedText.substring(0, currentSentence.length);
+        const trimmedText = typedUnits.slice(0, sentenceUnits.length).join('');
         event.target.value = trimmedText;
         const result = app.update_typing_progress(trimmedText);
         if (result) {
@@ -191,24 +196,36 @@ function handleTypingInput(event) {
     }
 }
 
+// Split into user-perceived characters to match the spans Rust renders
+function splitGraphemes(text) {
+    if (window.Intl && Intl.Segmenter) {
+        return Array.from(new Intl.Segmenter().segment(text), (part) => part.segment);
+    }
+    return Array.from(text);
+}
+
 function updateGameDisplay(typedText, result) {
     // Update character highlighting
     const chars = document.querySelectorAll('#sentence .char');
+    const typedUnits = splitGraphemes(typedText);
+    const caseInsensitive = app.case_insensitive;
     let correctCount = 0;
     let incorrectCount = 0;
     
     chars.forEach((char, index) => {
         char.classList.remove('correct', 'incorrect', 'current');
         
-        if (index < typedText.length) {
-            if (typedText[index] === char.textContent) {
+        if (index < typedUnits.length) {
+            const expected = caseInsensitive ? char.textContent.toLowerCase() : char.textContent;
+            const typed = caseInsensitive ? typedUnits[index].toLowerCase() : typedUnits[index];
+            if (typed === expected) {
                 char.classList.add('correct');
                 correctCount++;
             } else {
                 char.classList.add('incorrect');
                 incorrectCount++;
             }
-        } else if (index === typedText.length) {
+        } else if (index === typedUnits.length) {
             char.classList.add('current');
         }
     });
@@ -514,9 +531,38 @@ function clearTypingInput() {
     }
 }
 
+// Called by Rust once the post-sentence celebration delay has elapsed
+function advanceSentence() {
+    if (app) {
+        app.advance_sentence();
+    }
+}
+
+// Called by Rust once "GO!" has been on screen long enough
+function beginGameSession() {
+    if (app) {
+        app.start_game_session();
+    }
+}
+
+// Called by Rust in kiosk mode once the results have been shown long enough
+function kioskRestart() {
+    if (app) {
+        clearGameTimer();
+        app.restart_game();
+        startCountdownTimer();
+    }
+}
+
 function updateTimerDisplay(remaining) {
     const timerEl = document.getElementById('timer');
     if (timerEl) {
+        // Untimed sessions report an infinite remaining time
+        if (!Number.isFinite(remaining)) {
+            timerEl.textContent = '∞';
+            return;
+        }
+        
         const minutes = Math.floor(remaining / 60);
         const seconds = Math.floor(remaining % 60);
         
@@ -598,6 +644,9 @@ window.clearCountdownTimer = clearCountdownTimer;
 window.startGameTimer = startGameTimer;
 window.clearGameTimer = clearGameTimer;
 window.clearTypingInput = clearTypingInput;
+window.advanceSentence = advanceSentence;
+window.beginGameSession = beginGameSession;
+window.kioskRestart = kioskRestart;
 window.focusTypingInput = focusTypingInput;
 
 // Debug functions
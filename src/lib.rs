@@ -2,8 +2,9 @@ use wasm_bindgen::prelude::*;
 use js_sys::Date;
 use web_sys::{window, HtmlInputElement};
 use rand::prelude::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use serde::{Serialize, Deserialize};
+use unicode_segmentation::UnicodeSegmentation;
 
 #[wasm_bindgen]
 extern "C" {
@@ -19,15 +20,44 @@ extern "C" {
     #[wasm_bindgen(js_name = startGameTimer)]
     fn start_game_timer();
     
+    #[wasm_bindgen(js_name = clearGameTimer)]
+    fn clear_game_timer();
+    
     #[wasm_bindgen(js_name = clearTypingInput)]
     fn clear_typing_input();
+    
+    #[wasm_bindgen(js_name = advanceSentence)]
+    fn advance_sentence_hook();
+    
+    #[wasm_bindgen(js_name = beginGameSession)]
+    fn begin_game_session_hook();
+    
+    #[wasm_bindgen(js_name = kioskRestart)]
+    fn kiosk_restart_hook();
 }
 
 macro_rules! console_log {
     ($($t:tt)*) => (log(&format_args!($($t)*).to_string()))
 }
 
+const MIN_DURATION_SECONDS: u32 = 15;
+const MAX_DURATION_SECONDS: u32 = 600;
+const MIN_COUNTDOWN_SECONDS: u32 = 1;
+const MAX_COUNTDOWN_SECONDS: u32 = 10;
+const LEADERBOARD_SIZE: usize = 10;
+const MAX_WPM_SAMPLES: usize = 60;
+const MIN_CHARS_FOR_BEST_ACCURACY: usize = 20;
+const MAX_SENTENCES_PER_ROUND: u32 = 5;
+const MAX_GO_HOLD_MS: u32 = 3000;
+const MAX_AVATAR_CHARS: usize = 8;
+const MAX_SESSION_HISTORY: usize = 100;
+const MAX_SENTENCE_TARGET: u32 = 100;
+// Sentence length and average word length at which difficulty tops out
+const HARDEST_SENTENCE_CHARS: f64 = 150.0;
+const HARDEST_AVG_WORD_CHARS: f64 = 10.0;
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct UserData {
     pub name: String,
     pub language: String,
@@ -36,9 +66,120 @@ pub struct UserData {
     pub best_wpm: u32,
     pub best_accuracy: u32,
     pub total_sessions: u32,
+    pub wpm_mode: WpmMode,
+    pub max_wpm: u32,
+    pub languages: Vec<String>,
+    pub auto_advance_delay_ms: u32,
+    pub countdown_seconds: u32,
+    pub best_streak: u32,
+    pub strict_mode: bool,
+    pub time_per_language: HashMap<String, f64>,
+    pub chars_per_word: f64,
+    pub simplify_text: bool,
+    pub target_wpm: u32,
+    pub keyboard_layout: KeyboardLayout,
+    pub idle_timeout_ms: u32,
+    pub category: String,
+    pub last_wpm: Option<u32>,
+    pub last_accuracy: Option<u32>,
+    pub sentences_per_round: u32,
+    pub letters_only: bool,
+    pub adaptive_selection: bool,
+    pub mistake_counts: HashMap<char, u32>,
+    pub duration_presets: Vec<u32>,
+    pub blacklist: HashSet<String>,
+    pub error_sound_cooldown_ms: u32,
+    pub go_hold_ms: u32,
+    pub avatar: String,
+    pub kiosk_mode: bool,
+    pub kiosk_restart_delay_ms: u32,
+    pub session_history: Vec<SessionResult>,
+    pub trim_leading_space: bool,
+    pub completion_threshold: f64,
+    pub sentence_order: SentenceOrder,
+    pub duration_chosen: bool,
+    pub completed_sentences: HashSet<String>,
+    pub case_insensitive: bool,
+    // Sentence-count mode ends the session after this many sentences; 0 keeps it purely timed
+    pub sentence_target: u32,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+impl Default for UserData {
+    fn default() -> Self {
+        UserData {
+            name: String::new(),
+            language: "id".to_string(),
+            language_name: "Bahasa Indonesia".to_string(),
+            duration: 120,
+            best_wpm: 0,
+            best_accuracy: 0,
+            total_sessions: 0,
+            wpm_mode: WpmMode::Net,
+            max_wpm: 300,
+            languages: Vec::new(),
+            auto_advance_delay_ms: 0,
+            countdown_seconds: 5,
+            best_streak: 0,
+            strict_mode: false,
+            time_per_language: HashMap::new(),
+            chars_per_word: 5.0,
+            simplify_text: false,
+            target_wpm: 0,
+            keyboard_layout: KeyboardLayout::Qwerty,
+            idle_timeout_ms: 0,
+            category: String::new(),
+            last_wpm: None,
+            last_accuracy: None,
+            sentences_per_round: 1,
+            letters_only: false,
+            adaptive_selection: false,
+            mistake_counts: HashMap::new(),
+            duration_presets: vec![30, 60, 120, 300],
+            blacklist: HashSet::new(),
+            error_sound_cooldown_ms: 150,
+            go_hold_ms: 800,
+            avatar: "⭐".to_string(),
+            kiosk_mode: false,
+            kiosk_restart_delay_ms: 15000,
+            session_history: Vec::new(),
+            trim_leading_space: false,
+            completion_threshold: 1.0,
+            sentence_order: SentenceOrder::Random,
+            duration_chosen: false,
+            completed_sentences: HashSet::new(),
+            case_insensitive: false,
+            sentence_target: 0,
+        }
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum WpmMode {
+    Gross,
+    #[default]
+    Net,
+}
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum SentenceOrder {
+    #[default]
+    Random,
+    Sequential,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyboardLayout {
+    #[default]
+    Qwerty,
+    Azerty,
+    Dvorak,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SessionResult {
     pub wpm: u32,
     pub accuracy: u32,
@@ -46,9 +187,114 @@ pub struct SessionResult {
     pub correct_chars: u32,
     pub time_spent: f64,
     pub sentences_completed: u32,
+    pub new_wpm_record: bool,
+    pub new_accuracy_record: bool,
+    pub daily_challenge: bool,
+    pub goal_met: bool,
+    pub is_cpm: bool,
+    pub has_previous_session: bool,
+    pub wpm_delta: Option<i32>,
+    pub accuracy_delta: Option<i32>,
+    pub adjusted_wpm: u32,
+    pub ended_incomplete: bool,
+    pub words_typed: u32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SentenceResult {
+    pub sentence: String,
+    pub wpm: u32,
+    pub accuracy: u32,
+    pub time_spent: f64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LeaderboardEntry {
+    pub name: String,
+    pub wpm: u32,
+    pub accuracy: u32,
+    pub timestamp: f64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PlayerSummary {
+    pub name: String,
+    pub best_wpm: u32,
+    pub best_accuracy: u32,
+    pub total_sessions: u32,
+    pub best_streak: u32,
+}
+
+// Snapshot of a running session so a page reload can pick it back up
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct ActiveSession {
+    session_start_time: f64,
+    paused_duration: f64,
+    typed_chars: usize,
+    correct_chars: usize,
+    time_spent: f64,
+    sentences_completed: usize,
+    sentences_skipped: usize,
+    words_typed: usize,
+    current_streak: u32,
+    daily_challenge: bool,
+    sentence_language: String,
+    sentence: String,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct SentenceEntry {
+    category: &'static str,
+    text: &'static str,
+}
+
+// User-perceived characters, so a composed emoji or accented letter is one typed unit
+fn graphemes(text: &str) -> Vec<&str> {
+    text.graphemes(true).collect()
+}
+
+// Lowercase-only sentences for the letters_only warm-up, kept out of the regular pools
+fn warm_up_sentences(language: &str) -> &'static [&'static str] {
+    match language {
+        "en" => &[
+            "the little cat naps in the warm sun",
+            "a red bird sings on the old tree",
+            "we play ball with our good friends",
+        ],
+        "es" => &[
+            "el perro come pan en la casa",
+            "la luna sale y los niños duermen",
+            "mi amigo tiene una pelota azul",
+        ],
+        "fr" => &[
+            "le chat dort sur le lit chaud",
+            "la petite fille chante dans le parc",
+            "mon ami a un ballon rouge",
+        ],
+        "id" => &[
+            "kucing kecil tidur di atas kursi",
+            "adik makan nasi dan ikan goreng",
+            "burung terbang tinggi di langit biru",
+        ],
+        _ => &[],
+    }
+}
+
+fn tagged(entries: &[(&'static str, &'static str)]) -> Vec<SentenceEntry> {
+    entries.iter()
+        .map(|&(category, text)| SentenceEntry { category, text })
+        .collect()
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LanguageInfo {
+    pub code: String,
+    pub name: String,
+    pub practice_set: bool,
 }
 
 #[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum AppState {
     Loading,
     Welcome,
@@ -58,32 +304,90 @@ pub enum AppState {
     Playing,
     TimesUp,
     Results,
+    Practice,
+    Paused,
 }
 
 #[wasm_bindgen]
 pub struct TypingApp {
-    sentences: HashMap<String, Vec<&'static str>>,
+    sentences: HashMap<String, Vec<SentenceEntry>>,
+    countdown_messages: HashMap<String, Vec<String>>,
+    rng: SmallRng,
     user_data: UserData,
     session_result: Option<SessionResult>,
+    leaderboard: Vec<LeaderboardEntry>,
     app_state: AppState,
+    profiles: Vec<String>,
+    active_profile: String,
     
     // Game state
     current_sentence: String,
+    original_sentence: String,
+    sentence_language: String,
+    // Tutorial text set by the embedder rather than drawn from a pool
+    sentence_pinned: bool,
+    next_sentence: Option<(String, String)>,
+    played_sentences: Vec<(String, String)>,
+    replay_queue: VecDeque<(String, String)>,
+    sentence_bags: HashMap<String, VecDeque<&'static str>>,
+    sequential_index: usize,
     start_time: Option<f64>,
     end_time: Option<f64>,
     typed_chars: usize,
     correct_chars: usize,
     is_active: bool,
+    last_typed_len: usize,
+    blocked_at_index: Option<usize>,
+    first_error_index: Option<usize>,
+    overtyped_chars: usize,
+    last_typed_text: String,
+    practice_mode: bool,
+    zen_mode: bool,
+    daily_challenge: bool,
     
     // Session tracking
     session_start_time: Option<f64>,
+    paused: bool,
+    auto_paused: bool,
+    pause_started_at: Option<f64>,
+    paused_duration: f64,
     session_total_typed_chars: usize,
     session_total_correct_chars: usize,
     session_total_time_spent: f64,
+    session_language_time: HashMap<String, f64>,
     session_sentences_completed: usize,
+    session_sentences_skipped: usize,
+    session_words_typed: usize,
+    current_streak: u32,
+    sentence_results: Vec<SentenceResult>,
+    keystroke_intervals: Vec<f64>,
+    last_keystroke_time: Option<f64>,
+    last_activity_time: Option<f64>,
+    wpm_samples: Vec<f64>,
+    row_mistakes: HashMap<String, u32>,
+    backspace_count: u32,
+    total_keystrokes: u32,
     
     // UI state
     countdown_value: u32,
+    storage_available: bool,
+    awaiting_advance: bool,
+    advance_timeout_id: Option<u32>,
+    advance_closure: Option<Closure<dyn FnMut()>>,
+    go_timeout_id: Option<u32>,
+    go_closure: Option<Closure<dyn FnMut()>>,
+    kiosk_timeout_id: Option<u32>,
+    kiosk_closure: Option<Closure<dyn FnMut()>>,
+    logging_enabled: bool,
+    auto_generate: bool,
+    awaiting_next_sentence: bool,
+    last_error_sound_time: Option<f64>,
+    
+    // Embedder callbacks
+    state_change_callback: Option<js_sys::Function>,
+    correct_sound_callback: Option<js_sys::Function>,
+    error_sound_callback: Option<js_sys::Function>,
+    session_complete_callback: Option<js_sys::Function>,
 }
 
 #[wasm_bindgen]
@@ -92,173 +396,358 @@ impl TypingApp {
     pub fn new() -> TypingApp {
         let mut sentences = HashMap::new();
         
-        sentences.insert("en".to_string(), vec![
-            "Once upon a time, there was a brave little mouse who loved cheese.",
-            "The magical unicorn danced in the rainbow garden with butterfly friends.",
-            "A kind dragon shared cookies with all the forest animals today.",
-            "The happy cat played with a red ball in the sunny backyard.",
-            "Three little pigs built houses and became best friends forever.",
-            "A wise owl taught young animals how to read and write stories.",
-            "The friendly bear found honey and shared it with busy bees.",
-            "A beautiful princess rescued a lost puppy and they went home together.",
-            "The singing bird helped flowers grow by playing sweet music every morning.",
-            "A funny monkey swung through trees and made all animals laugh loudly.",
-            "The gentle elephant carried small animals across the big river safely.",
-            "A brave knight helped a lost kitten find its way back home.",
-            "The magic fairy sprinkled stardust and made all wishes come true.",
-            "A sleepy bunny counted carrots and dreamed of adventure in the meadow.",
-            "The colorful parrot taught children how to say hello in many languages.",
-        ]);
+        sentences.insert("en".to_string(), tagged(&[
+            ("fairy-tales", "Once upon a time, there was a brave little mouse who loved cheese."),
+            ("fairy-tales", "The magical unicorn danced in the rainbow garden with butterfly friends."),
+            ("fairy-tales", "A kind dragon shared cookies with all the forest animals today."),
+            ("animals", "The happy cat played with a red ball in the sunny backyard."),
+            ("fairy-tales", "Three little pigs built houses and became best friends forever."),
+            ("animals", "A wise owl taught young animals how to read and write stories."),
+            ("animals", "The friendly bear found honey and shared it with busy bees."),
+            ("fairy-tales", "A beautiful princess rescued a lost puppy and they went home together."),
+            ("nature", "The singing bird helped flowers grow by playing sweet music every morning."),
+            ("animals", "A funny monkey swung through trees and made all animals laugh loudly."),
+            ("animals", "The gentle elephant carried small animals across the big river safely."),
+            ("fairy-tales", "A brave knight helped a lost kitten find its way back home."),
+            ("fairy-tales", "The magic fairy sprinkled stardust and made all wishes come true."),
+            ("animals", "A sleepy bunny counted carrots and dreamed of adventure in the meadow."),
+            ("animals", "The colorful parrot taught children how to say hello in many languages."),
+        ]));
         
-        sentences.insert("es".to_string(), vec![
-            "El gatito mágico jugaba con una pelota de colores en el jardín.",
-            "La princesa valiente salvó al pequeño conejito perdido en el bosque.",
-            "Un dragón amigable compartió dulces con todos los niños del pueblo.",
-            "El oso feliz encontró miel y la compartió con las abejas trabajadoras.",
-            "Tres cerditos construyeron casas y se hicieron mejores amigos para siempre.",
-            "La mariposa colorida voló entre las flores cantando una canción bonita.",
-            "El elefante gentil ayudó a los animales pequeños a cruzar el río.",
-            "Una hada mágica concedió deseos a todos los niños buenos.",
-            "El ratoncito valiente encontró queso y compartió con su familia.",
-            "El búho sabio enseñó a leer y escribir a todos los animales.",
-        ]);
+        sentences.insert("es".to_string(), tagged(&[
+            ("fairy-tales", "El gatito mágico jugaba con una pelota de colores en el jardín."),
+            ("fairy-tales", "La princesa valiente salvó al pequeño conejito perdido en el bosque."),
+            ("fairy-tales", "Un dragón amigable compartió dulces con todos los niños del pueblo."),
+            ("animals", "El oso feliz encontró miel y la compartió con las abejas trabajadoras."),
+            ("fairy-tales", "Tres cerditos construyeron casas y se hicieron mejores amigos para siempre."),
+            ("nature", "La mariposa colorida voló entre las flores cantando una canción bonita."),
+            ("animals", "El elefante gentil ayudó a los animales pequeños a cruzar el río."),
+            ("fairy-tales", "Una hada mágica concedió deseos a todos los niños buenos."),
+            ("animals", "El ratoncito valiente encontró queso y compartió con su familia."),
+            ("animals", "El búho sabio enseñó a leer y escribir a todos los animales."),
+        ]));
         
-        sentences.insert("fr".to_string(), vec![
-            "Le petit chat magique jouait avec une balle colorée dans le jardin.",
-            "Une princesse courageuse a sauvé un petit lapin perdu dans la forêt.",
-            "Le dragon gentil a partagé des bonbons avec tous les enfants du village.",
-            "L'ours heureux a trouvé du miel et l'a partagé avec les abeilles.",
-            "Trois petits cochons ont construit des maisons et sont devenus amis.",
-            "Le papillon coloré a volé parmi les fleurs en chantant une belle chanson.",
-            "L'éléphant gentil a aidé les petits animaux à traverser la rivière.",
-            "Une fée magique a exaucé les vœux de tous les bons enfants.",
-            "La petite souris courageuse a trouvé du fromage pour sa famille.",
-            "Le hibou sage a appris à lire et écrire à tous les animaux.",
-        ]);
+        sentences.insert("fr".to_string(), tagged(&[
+            ("fairy-tales", "Le petit chat magique jouait avec une balle colorée dans le jardin."),
+            ("fairy-tales", "Une princesse courageuse a sauvé un petit lapin perdu dans la forêt."),
+            ("fairy-tales", "Le dragon gentil a partagé des bonbons avec tous les enfants du village."),
+            ("animals", "L'ours heureux a trouvé du miel et l'a partagé avec les abeilles."),
+            ("fairy-tales", "Trois petits cochons ont construit des maisons et sont devenus amis."),
+            ("nature", "Le papillon coloré a volé parmi les fleurs en chantant une belle chanson."),
+            ("animals", "L'éléphant gentil a aidé les petits animaux à traverser la rivière."),
+            ("fairy-tales", "Une fée magique a exaucé les vœux de tous les bons enfants."),
+            ("animals", "La petite souris courageuse a trouvé du fromage pour sa famille."),
+            ("animals", "Le hibou sage a appris à lire et écrire à tous les animaux."),
+        ]));
 
-        sentences.insert("id".to_string(), vec![
-            "Dahulu kala hiduplah seekor kucing kecil yang suka bermain bola warna-warni.",
-            "Putri pemberani menyelamatkan kelinci kecil yang tersesat di hutan ajaib.",
-            "Naga baik hati membagi permen kepada semua anak-anak di desa.",
-            "Beruang gembira menemukan madu dan membaginya dengan lebah-lebah pekerja.",
-            "Tiga babi kecil membangun rumah dan menjadi sahabat selamanya.",
-            "Kupu-kupu cantik terbang di antara bunga sambil bernyanyi lagu indah.",
-            "Gajah ramah membantu hewan kecil menyeberangi sungai yang deras.",
-            "Peri ajaib mengabulkan permintaan semua anak-anak yang baik.",
-            "Tikus kecil yang berani menemukan keju untuk keluarganya.",
-            "Burung hantu bijak mengajar semua hewan cara membaca dan menulis.",
-            "Kelinci cerdas melompat-lompat gembira di padang rumput hijau.",
-            "Singa raja hutan berbagi makanan dengan semua temannya.",
-            "Kura-kura lambat tapi pasti memenangkan lomba lari yang seru.",
-            "Lumba-lumba pintar bermain air bersama ikan-ikan kecil di laut.",
-            "Rusa anggun berlari cepat melewati hutan yang rimbun dan sejuk.",
-            "Monyet lucu berayun dari pohon ke pohon sambil tertawa riang.",
-            "Tupai rajin mengumpulkan kacang untuk persiapan musim dingin nanti.",
-            "Merpati putih membawa pesan cinta dari satu tempat ke tempat lain.",
-            "Katak hijau bernyanyi merdu di kolam yang jernih dan tenang.",
-            "Lebah pekerja keras membuat madu manis di sarang yang aman.",
-            "Ayam jago berkokok keras membangunkan semua penghuni desa pagi hari.",
-            "Bebek kuning berenang santai di danau yang biru dan tenang.",
-            "Kambing putih melompat-lompat senang di atas bukit yang tinggi.",
-            "Sapi coklat memberikan susu segar untuk anak-anak setiap pagi.",
-            "Kuda putih berlari kencang membawa putri ke istana yang megah.",
-            "Anjing setia menjaga rumah dan melindungi keluarga dengan baik.",
-            "Kucing persia tidur nyenyak di atas bantal yang empuk dan hangat.",
-            "Hamster kecil berlari di roda mainan sambil makan biji bunga matahari.",
-            "Burung beo pintar meniru suara manusia dengan sangat jelas.",
-            "Ikan mas berenang indah di akuarium yang bersih dan jernih.",
-            "Penyu tua bercerita tentang petualangan di dasar laut yang dalam.",
-            "Burung hantu malam terbang diam-diam mencari makanan di kegelapan.",
-            "Serigala abu-abu melolong panjang di bawah sinar bulan purnama.",
-            "Rubah merah berlari gesit menghindari pemburu di hutan lebat.",
-            "Berang-berang rajin membangun bendungan di sungai yang mengalir deras.",
-            "Landak berduri melindungi diri dari bahaya dengan cara menggulung badan.",
-            "Rusa kutub putih berjalan anggun di atas salju yang tebal.",
-            "Pinguin lucu berjalan bergoyang di atas es yang licin dan dingin.",
-            "Burung flamingo merah muda berdiri dengan satu kaki di danau.",
-            "Jerapah tinggi makan daun segar dari puncak pohon yang tinggi.",
-            "Zebra belang hitam putih berlari bersama kawanannya di padang savana.",
-            "Badak abu-abu berkubang lumpur untuk melindungi kulitnya dari matahari.",
-            "Cheetah tercepat mengejar mangsanya dengan kecepatan yang luar biasa.",
-            "Orangutan cerdas berayun dari dahan ke dahan di hutan Kalimantan.",
-            "Komodo besar berjemur di bawah sinar matahari pagi yang hangat.",
-            "Burung cendrawasih cantik menari indah untuk menarik perhatian pasangannya.",
-            "Gajah Sumatera mandi lumpur di sungai untuk mendinginkan tubuhnya.",
-            "Harimau Sumatera berburu mangsa di hutan hujan yang lebat.",
-            "Burung garuda terbang tinggi melintasi langit biru Indonesia yang indah.",
-            "Anak-anak bermain layang-layang warna-warni di lapangan yang luas dan hijau."
-        ]);
+        sentences.insert("id".to_string(), tagged(&[
+            ("fairy-tales", "Dahulu kala hiduplah seekor kucing kecil yang suka bermain bola warna-warni."),
+            ("fairy-tales", "Putri pemberani menyelamatkan kelinci kecil yang tersesat di hutan ajaib."),
+            ("fairy-tales", "Naga baik hati membagi permen kepada semua anak-anak di desa."),
+            ("animals", "Beruang gembira menemukan madu dan membaginya dengan lebah-lebah pekerja."),
+            ("fairy-tales", "Tiga babi kecil membangun rumah dan menjadi sahabat selamanya."),
+            ("nature", "Kupu-kupu cantik terbang di antara bunga sambil bernyanyi lagu indah."),
+            ("animals", "Gajah ramah membantu hewan kecil menyeberangi sungai yang deras."),
+            ("fairy-tales", "Peri ajaib mengabulkan permintaan semua anak-anak yang baik."),
+            ("animals", "Tikus kecil yang berani menemukan keju untuk keluarganya."),
+            ("animals", "Burung hantu bijak mengajar semua hewan cara membaca dan menulis."),
+            ("animals", "Kelinci cerdas melompat-lompat gembira di padang rumput hijau."),
+            ("animals", "Singa raja hutan berbagi makanan dengan semua temannya."),
+            ("animals", "Kura-kura lambat tapi pasti memenangkan lomba lari yang seru."),
+            ("animals", "Lumba-lumba pintar bermain air bersama ikan-ikan kecil di laut."),
+            ("animals", "Rusa anggun berlari cepat melewati hutan yang rimbun dan sejuk."),
+            ("animals", "Monyet lucu berayun dari pohon ke pohon sambil tertawa riang."),
+            ("animals", "Tupai rajin mengumpulkan kacang untuk persiapan musim dingin nanti."),
+            ("animals", "Merpati putih membawa pesan cinta dari satu tempat ke tempat lain."),
+            ("animals", "Katak hijau bernyanyi merdu di kolam yang jernih dan tenang."),
+            ("animals", "Lebah pekerja keras membuat madu manis di sarang yang aman."),
+            ("animals", "Ayam jago berkokok keras membangunkan semua penghuni desa pagi hari."),
+            ("animals", "Bebek kuning berenang santai di danau yang biru dan tenang."),
+            ("animals", "Kambing putih melompat-lompat senang di atas bukit yang tinggi."),
+            ("animals", "Sapi coklat memberikan susu segar untuk anak-anak setiap pagi."),
+            ("fairy-tales", "Kuda putih berlari kencang membawa putri ke istana yang megah."),
+            ("animals", "Anjing setia menjaga rumah dan melindungi keluarga dengan baik."),
+            ("animals", "Kucing persia tidur nyenyak di atas bantal yang empuk dan hangat."),
+            ("animals", "Hamster kecil berlari di roda mainan sambil makan biji bunga matahari."),
+            ("animals", "Burung beo pintar meniru suara manusia dengan sangat jelas."),
+            ("animals", "Ikan mas berenang indah di akuarium yang bersih dan jernih."),
+            ("animals", "Penyu tua bercerita tentang petualangan di dasar laut yang dalam."),
+            ("animals", "Burung hantu malam terbang diam-diam mencari makanan di kegelapan."),
+            ("animals", "Serigala abu-abu melolong panjang di bawah sinar bulan purnama."),
+            ("animals", "Rubah merah berlari gesit menghindari pemburu di hutan lebat."),
+            ("animals", "Berang-berang rajin membangun bendungan di sungai yang mengalir deras."),
+            ("animals", "Landak berduri melindungi diri dari bahaya dengan cara menggulung badan."),
+            ("animals", "Rusa kutub putih berjalan anggun di atas salju yang tebal."),
+            ("animals", "Pinguin lucu berjalan bergoyang di atas es yang licin dan dingin."),
+            ("animals", "Burung flamingo merah muda berdiri dengan satu kaki di danau."),
+            ("animals", "Jerapah tinggi makan daun segar dari puncak pohon yang tinggi."),
+            ("animals", "Zebra belang hitam putih berlari bersama kawanannya di padang savana."),
+            ("animals", "Badak abu-abu berkubang lumpur untuk melindungi kulitnya dari matahari."),
+            ("animals", "Cheetah tercepat mengejar mangsanya dengan kecepatan yang luar biasa."),
+            ("animals", "Orangutan cerdas berayun dari dahan ke dahan di hutan Kalimantan."),
+            ("animals", "Komodo besar berjemur di bawah sinar matahari pagi yang hangat."),
+            ("animals", "Burung cendrawasih cantik menari indah untuk menarik perhatian pasangannya."),
+            ("animals", "Gajah Sumatera mandi lumpur di sungai untuk mendinginkan tubuhnya."),
+            ("animals", "Harimau Sumatera berburu mangsa di hutan hujan yang lebat."),
+            ("animals", "Burung garuda terbang tinggi melintasi langit biru Indonesia yang indah."),
+            ("nature", "Anak-anak bermain layang-layang warna-warni di lapangan yang luas dan hijau."),
+        ]));
+
+        sentences.insert("ar".to_string(), tagged(&[
+            ("animals", "القطة الصغيرة تلعب بالكرة الحمراء في الحديقة."),
+            ("animals", "الأرنب السريع يقفز فوق العشب الأخضر."),
+            ("animals", "الأسد الشجاع يحمي أصدقاءه في الغابة."),
+            ("nature", "الفراشة الملونة تطير بين الزهور الجميلة."),
+            ("animals", "البومة الحكيمة تعلم الحيوانات القراءة والكتابة."),
+            ("animals", "الفيل اللطيف يساعد الحيوانات الصغيرة على عبور النهر."),
+            ("nature", "الطائر المغرد يغني أغنية جميلة كل صباح."),
+            ("animals", "الدب السعيد وجد العسل وشاركه مع النحل."),
+            ("fairy-tales", "الأميرة الطيبة أنقذت جروا صغيرا ضائعا."),
+            ("animals", "السلحفاة البطيئة فازت في السباق بالصبر."),
+        ]));
+
+        sentences.insert("zh".to_string(), tagged(&[
+            ("animals", "小猫在花园里玩红色的球。"),
+            ("animals", "小兔子在绿色的草地上跳来跳去。"),
+            ("animals", "勇敢的狮子保护森林里的朋友。"),
+            ("nature", "彩色的蝴蝶在美丽的花朵中飞舞。"),
+            ("animals", "聪明的猫头鹰教小动物们读书写字。"),
+            ("animals", "善良的大象帮助小动物们过河。"),
+            ("nature", "小鸟每天早上唱一首好听的歌。"),
+            ("animals", "快乐的小熊找到了蜂蜜，和蜜蜂一起分享。"),
+            ("fairy-tales", "善良的公主救了一只迷路的小狗。"),
+            ("fairy-tales", "慢慢的乌龟用耐心赢得了比赛。"),
+        ]));
+
+        // Practice sets drill digits and punctuation rather than a spoken language
+        sentences.insert("numbers".to_string(), tagged(&[
+            ("numbers", "The year 2024 had 366 days because it was a leap year."),
+            ("numbers", "There are 12 months, 52 weeks and 7 days in every week."),
+            ("numbers", "Our class has 24 students, 13 girls and 11 boys."),
+            ("numbers", "The rocket counted down from 10 to 1 and flew 400 kilometers high."),
+            ("numbers", "A dozen eggs is 12, so two dozen eggs is 24."),
+            ("numbers", "The library opens at 9 and closes at 5 every weekday."),
+            ("numbers", "My phone number has 10 digits like 0812 3456 7890."),
+            ("numbers", "The tiger is 3 years old and weighs 150 kilograms."),
+            ("numbers", "We planted 45 flowers in 5 rows of 9 flowers each."),
+            ("numbers", "The bus leaves at 7:45 and arrives at 8:30."),
+        ]));
+
+        sentences.insert("symbols".to_string(), tagged(&[
+            ("symbols", "Wow! Did you see the shooting star? It was amazing!"),
+            ("symbols", "Mom said: \"Wash your hands, then eat your snack.\""),
+            ("symbols", "Email me at tiger@example.com (only on weekends)."),
+            ("symbols", "Apples cost $2, pears cost $3 & grapes cost $5."),
+            ("symbols", "The score was 3-2; our team won the match!"),
+            ("symbols", "Use #fun and @friends when you share your story."),
+            ("symbols", "10% of 50 is 5, and 50% of 10 is also 5."),
+            ("symbols", "Bring these: pencils, paper, glue [and scissors]."),
+            ("symbols", "Is 5 + 3 = 8? Yes, and 8 * 2 = 16!"),
+            ("symbols", "The path was long/winding ~ but we made it :)"),
+        ]));
 
         // Special Independence Day mode with proclamation and struggle quotes
-        sentences.insert("merdeka".to_string(), vec![
-            "Kami bangsa Indonesia dengan ini menyatakan kemerdekaan Indonesia.",
-            "Hal-hal yang mengenai pemindahan kekuasaan dan lain-lain diselenggarakan dengan cara saksama dan dalam tempo yang sesingkat-singkatnya.",
-            "Jakarta 17 Agustus 1945 atas nama bangsa Indonesia Soekarno Hatta.",
-            "Perjuangan kita belum selesai selama masih ada rakyat yang menderita.",
-            "Merdeka atau mati adalah semboyan yang mengalir dalam darah pejuang.",
-            "Berikan aku seribu orang tua niscaya akan kucabut Semeru dari akarnya.",
-            "Berikan aku sepuluh pemuda niscaya akan kuguncangkan dunia.",
-            "Jadilah kamu manusia yang pada kelahiranmu semua orang tertawa bahagia tetapi hanya kamu sendiri yang menangis.",
-            "Dan pada kematianmu semua orang menangis sedih tetapi hanya kamu sendiri yang tersenyum bahagia.",
-            "Bangsa yang besar adalah bangsa yang menghormati jasa pahlawannya.",
-            "Gantungkan cita-citamu setinggi langit ketahuilah kepribadianmu dan jadilah diri sendiri.",
-            "Jangan sekali-kali meninggalkan sejarah karena sejarah adalah guru yang paling bijaksana.",
-            "Perjuanganku lebih mudah karena mengusir penjajah tetapi perjuanganmu akan lebih sulit karena melawan bangsamu sendiri.",
-            "Apabila dalam diri seseorang masih ada rasa malu dan takut untuk berbuat kebaikan maka jaminan bagi orang tersebut adalah tidak akan bertemunya ia dengan kemajuan selangkah pun.",
-            "Kita harus memiliki keinginan yang menyala-nyala untuk merdeka.",
-            "Kemerdekaan hanya dapat dicapai dan dimiliki oleh bangsa yang jiwanya berkobar-kobar dengan tekad merdeka.",
-            "Rakyat Indonesia menuntut Indonesia merdeka.",
-            "Lebih baik mati berkalang tanah daripada hidup bercermin bangkai.",
-            "Sekali merdeka tetap merdeka.",
-            "Indonesia merdeka adalah hak segala bangsa dan oleh sebab itu penjajahan di atas dunia harus dihapuskan karena tidak sesuai dengan perikemanusiaan dan perikeadilan.",
-            "Pancasila sebagai dasar negara Indonesia adalah pedoman hidup bangsa.",
-            "Bhinneka Tunggal Ika meskipun berbeda-beda tetapi tetap satu jua.",
-            "Garuda Pancasila adalah lambang negara Republik Indonesia.",
-            "Indonesia Raya adalah lagu kebangsaan Indonesia yang menggetarkan jiwa.",
-            "Merah Putih adalah bendera pusaka yang dihormati seluruh bangsa.",
-            "Sang Saka Merah Putih berkibar dengan gagah di tiang bendera.",
-            "Dirgahayu Indonesia ke-78 tahun 2023 tetap jaya dan bermartabat.",
-            "Kemerdekaan Indonesia diproklamasikan pada tanggal 17 Agustus 1945.",
-            "Hari kemerdekaan Indonesia diperingati setiap tanggal 17 Agustus.",
-            "Upacara bendera dilaksanakan untuk mengenang jasa para pahlawan."
-        ]);
+        sentences.insert("merdeka".to_string(), tagged(&[
+            ("proclamation", "Kami bangsa Indonesia dengan ini menyatakan kemerdekaan Indonesia."),
+            ("proclamation", "Hal-hal yang mengenai pemindahan kekuasaan dan lain-lain diselenggarakan dengan cara saksama dan dalam tempo yang sesingkat-singkatnya."),
+            ("proclamation", "Jakarta 17 Agustus 1945 atas nama bangsa Indonesia Soekarno Hatta."),
+            ("struggle-quotes", "Perjuangan kita belum selesai selama masih ada rakyat yang menderita."),
+            ("struggle-quotes", "Merdeka atau mati adalah semboyan yang mengalir dalam darah pejuang."),
+            ("struggle-quotes", "Berikan aku seribu orang tua niscaya akan kucabut Semeru dari akarnya."),
+            ("struggle-quotes", "Berikan aku sepuluh pemuda niscaya akan kuguncangkan dunia."),
+            ("struggle-quotes", "Jadilah kamu manusia yang pada kelahiranmu semua orang tertawa bahagia tetapi hanya kamu sendiri yang menangis."),
+            ("struggle-quotes", "Dan pada kematianmu semua orang menangis sedih tetapi hanya kamu sendiri yang tersenyum bahagia."),
+            ("struggle-quotes", "Bangsa yang besar adalah bangsa yang menghormati jasa pahlawannya."),
+            ("struggle-quotes", "Gantungkan cita-citamu setinggi langit ketahuilah kepribadianmu dan jadilah diri sendiri."),
+            ("struggle-quotes", "Jangan sekali-kali meninggalkan sejarah karena sejarah adalah guru yang paling bijaksana."),
+            ("struggle-quotes", "Perjuanganku lebih mudah karena mengusir penjajah tetapi perjuanganmu akan lebih sulit karena melawan bangsamu sendiri."),
+            ("struggle-quotes", "Apabila dalam diri seseorang masih ada rasa malu dan takut untuk berbuat kebaikan maka jaminan bagi orang tersebut adalah tidak akan bertemunya ia dengan kemajuan selangkah pun."),
+            ("struggle-quotes", "Kita harus memiliki keinginan yang menyala-nyala untuk merdeka."),
+            ("struggle-quotes", "Kemerdekaan hanya dapat dicapai dan dimiliki oleh bangsa yang jiwanya berkobar-kobar dengan tekad merdeka."),
+            ("struggle-quotes", "Rakyat Indonesia menuntut Indonesia merdeka."),
+            ("struggle-quotes", "Lebih baik mati berkalang tanah daripada hidup bercermin bangkai."),
+            ("struggle-quotes", "Sekali merdeka tetap merdeka."),
+            ("proclamation", "Indonesia merdeka adalah hak segala bangsa dan oleh sebab itu penjajahan di atas dunia harus dihapuskan karena tidak sesuai dengan perikemanusiaan dan perikeadilan."),
+            ("national-symbols", "Pancasila sebagai dasar negara Indonesia adalah pedoman hidup bangsa."),
+            ("national-symbols", "Bhinneka Tunggal Ika meskipun berbeda-beda tetapi tetap satu jua."),
+            ("national-symbols", "Garuda Pancasila adalah lambang negara Republik Indonesia."),
+            ("national-symbols", "Indonesia Raya adalah lagu kebangsaan Indonesia yang menggetarkan jiwa."),
+            ("national-symbols", "Merah Putih adalah bendera pusaka yang dihormati seluruh bangsa."),
+            ("national-symbols", "Sang Saka Merah Putih berkibar dengan gagah di tiang bendera."),
+            ("national-symbols", "Dirgahayu Indonesia ke-78 tahun 2023 tetap jaya dan bermartabat."),
+            ("proclamation", "Kemerdekaan Indonesia diproklamasikan pada tanggal 17 Agustus 1945."),
+            ("independence-day", "Hari kemerdekaan Indonesia diperingati setiap tanggal 17 Agustus."),
+            ("independence-day", "Upacara bendera dilaksanakan untuk mengenang jasa para pahlawan."),
+        ]));
 
-        let user_data = UserData {
-            name: String::new(),
-            language: "id".to_string(),
-            language_name: "Bahasa Indonesia".to_string(),
-            duration: 120,
-            best_wpm: 0,
-            best_accuracy: 0,
-            total_sessions: 0,
-        };
+        // Countdown messages indexed by the number shown, from "GO!" (0) up to 5
+        let mut countdown_messages: HashMap<String, Vec<String>> = HashMap::new();
+        
+        countdown_messages.insert("en".to_string(), [
+            "GO! Help our magical friend! 🌟",
+            "Remember: slow and steady wins the race! 🐢",
+            "Take your time and be careful! 🎯",
+            "Put your hands on the keyboard like a pianist! 🎹",
+            "Get ready for a magical adventure! ✨",
+            "Wiggle your fingers and take a deep breath! 🖐️",
+        ].iter().map(|m| m.to_string()).collect());
+        
+        countdown_messages.insert("es".to_string(), [
+            "¡YA! ¡Ayuda a nuestro amigo mágico! 🌟",
+            "Recuerda: ¡despacio y con calma se gana la carrera! 🐢",
+            "¡Tómate tu tiempo y ten cuidado! 🎯",
+            "¡Pon las manos en el teclado como un pianista! 🎹",
+            "¡Prepárate para una aventura mágica! ✨",
+            "¡Mueve los dedos y respira hondo! 🖐️",
+        ].iter().map(|m| m.to_string()).collect());
+        
+        countdown_messages.insert("fr".to_string(), [
+            "C'est parti ! Aide notre ami magique ! 🌟",
+            "Rappelle-toi : rien ne sert de courir, il faut partir à point ! 🐢",
+            "Prends ton temps et fais attention ! 🎯",
+            "Pose tes mains sur le clavier comme un pianiste ! 🎹",
+            "Prépare-toi pour une aventure magique ! ✨",
+            "Remue tes doigts et respire profondément ! 🖐️",
+        ].iter().map(|m| m.to_string()).collect());
+        
+        let indonesian_messages: Vec<String> = [
+            "MULAI! Bantu teman ajaib kita! 🌟",
+            "Ingat: pelan-pelan asal selamat! 🐢",
+            "Santai saja dan hati-hati ya! 🎯",
+            "Letakkan tanganmu di keyboard seperti pianis! 🎹",
+            "Bersiaplah untuk petualangan ajaib! ✨",
+            "Gerakkan jarimu dan tarik napas dalam-dalam! 🖐️",
+        ].iter().map(|m| m.to_string()).collect();
+        countdown_messages.insert("merdeka".to_string(), indonesian_messages.clone());
+        countdown_messages.insert("id".to_string(), indonesian_messages);
 
         TypingApp {
             sentences,
-            user_data,
+            countdown_messages,
+            rng: SmallRng::from_entropy(),
+            user_data: UserData::default(),
             session_result: None,
+            leaderboard: Vec::new(),
             app_state: AppState::Loading,
+            profiles: Vec::new(),
+            active_profile: String::new(),
             current_sentence: String::new(),
+            original_sentence: String::new(),
+            sentence_language: String::new(),
+            sentence_pinned: false,
+            next_sentence: None,
+            played_sentences: Vec::new(),
+            replay_queue: VecDeque::new(),
+            sentence_bags: HashMap::new(),
+            sequential_index: 0,
             start_time: None,
             end_time: None,
             typed_chars: 0,
             correct_chars: 0,
             is_active: false,
+            last_typed_len: 0,
+            blocked_at_index: None,
+            first_error_index: None,
+            overtyped_chars: 0,
+            last_typed_text: String::new(),
+            practice_mode: false,
+            zen_mode: false,
+            daily_challenge: false,
             session_start_time: None,
+            paused: false,
+            auto_paused: false,
+            pause_started_at: None,
+            paused_duration: 0.0,
             session_total_typed_chars: 0,
             session_total_correct_chars: 0,
             session_total_time_spent: 0.0,
+            session_language_time: HashMap::new(),
             session_sentences_completed: 0,
+            session_sentences_skipped: 0,
+            session_words_typed: 0,
+            current_streak: 0,
+            sentence_results: Vec::new(),
+            keystroke_intervals: Vec::new(),
+            last_keystroke_time: None,
+            last_activity_time: None,
+            wpm_samples: Vec::new(),
+            row_mistakes: HashMap::new(),
+            backspace_count: 0,
+            total_keystrokes: 0,
             countdown_value: 5,
+            storage_available: true,
+            awaiting_advance: false,
+            advance_timeout_id: None,
+            advance_closure: None,
+            go_timeout_id: None,
+            go_closure: None,
+            kiosk_timeout_id: None,
+            kiosk_closure: None,
+            logging_enabled: true,
+            auto_generate: true,
+            awaiting_next_sentence: false,
+            last_error_sound_time: None,
+            state_change_callback: None,
+            correct_sound_callback: None,
+            error_sound_callback: None,
+            session_complete_callback: None,
         }
     }
 
     #[wasm_bindgen]
     pub fn initialize(&mut self) {
+        self.load_profiles();
         self.load_user_data();
+        self.load_leaderboard();
+        if self.resume_active_session() {
+            return;
+        }
         self.show_screen("welcome-screen");
-        self.app_state = AppState::Welcome;
-        console_log!("TypingApp initialized");
+        self.set_state(AppState::Welcome);
+        self.log_debug("TypingApp initialized");
+    }
+
+    #[wasm_bindgen]
+    pub fn set_state_change_callback(&mut self, callback: js_sys::Function) {
+        self.state_change_callback = Some(callback);
+    }
+
+    #[wasm_bindgen]
+    pub fn set_correct_sound(&mut self, callback: js_sys::Function) {
+        self.correct_sound_callback = Some(callback);
+    }
+
+    #[wasm_bindgen]
+    pub fn set_error_sound(&mut self, callback: js_sys::Function) {
+        self.error_sound_callback = Some(callback);
+    }
+
+    #[wasm_bindgen]
+    pub fn set_session_complete_callback(&mut self, callback: js_sys::Function) {
+        self.session_complete_callback = Some(callback);
+    }
+
+    #[wasm_bindgen]
+    pub fn set_logging_enabled(&mut self, enabled: bool) {
+        self.logging_enabled = enabled;
+    }
+
+    #[wasm_bindgen]
+    pub fn set_auto_generate(&mut self, enabled: bool) {
+        self.auto_generate = enabled;
+    }
+
+    fn log_debug(&self, msg: &str) {
+        if self.logging_enabled {
+            log(msg);
+        }
+    }
+
+    fn set_state(&mut self, state: AppState) {
+        self.app_state = state;
+        if let Some(callback) = &self.state_change_callback {
+            callback.call1(&JsValue::NULL, &JsValue::from(state as u32)).ok();
+        }
     }
 
     #[wasm_bindgen]
@@ -271,534 +760,4374 @@ impl TypingApp {
         false
     }
 
+    #[wasm_bindgen]
+    pub fn set_avatar(&mut self, avatar: &str) -> bool {
+        let avatar = avatar.trim();
+        // Room for multi-codepoint emoji such as skin tones or ZWJ sequences
+        if avatar.is_empty() || avatar.chars().count() > MAX_AVATAR_CHARS {
+            return false;
+        }
+        self.user_data.avatar = avatar.to_string();
+        self.save_user_data();
+        self.update_personalization();
+        true
+    }
+
     #[wasm_bindgen]
     pub fn set_language(&mut self, lang_code: &str, lang_name: &str) {
         if self.sentences.contains_key(lang_code) {
             self.user_data.language = lang_code.to_string();
             self.user_data.language_name = lang_name.to_string();
+            self.user_data.languages.clear();
+            if !self.user_data.duration_chosen {
+                self.user_data.duration = self.default_duration_for(lang_code);
+            }
             self.save_user_data();
         }
     }
 
+    // Long proclamation texts get extra time, short drills less, until the player picks their own
     #[wasm_bindgen]
-    pub fn set_duration(&mut self, duration: u32) {
-        self.user_data.duration = duration;
-        self.save_user_data();
+    pub fn default_duration_for(&self, lang_code: &str) -> u32 {
+        match lang_code {
+            "merdeka" => 180,
+            "zh" => 90,
+            "numbers" | "symbols" => 60,
+            _ => UserData::default().duration,
+        }
     }
 
     #[wasm_bindgen]
-    pub fn proceed_to_language(&mut self) {
-        if !self.user_data.name.is_empty() {
-            self.show_screen("language-screen");
-            self.app_state = AppState::Language;
+    pub fn set_languages(&mut self, codes: Vec<String>) -> bool {
+        if codes.is_empty() || !codes.iter().all(|code| self.sentences.contains_key(code)) {
+            self.log_debug(&format!("Rejected language list: {:?}", codes));
+            return false;
         }
+        
+        self.user_data.language = codes[0].clone();
+        self.user_data.language_name = Self::language_display_name(&codes[0]).to_string();
+        self.user_data.languages = codes;
+        self.save_user_data();
+        true
     }
 
     #[wasm_bindgen]
-    pub fn proceed_to_timer(&mut self) {
-        self.show_screen("timer-screen");
-        self.app_state = AppState::Timer;
+    pub fn set_duration(&mut self, duration: u32) -> bool {
+        if (MIN_DURATION_SECONDS..=MAX_DURATION_SECONDS).contains(&duration) {
+            self.user_data.duration = duration;
+            self.user_data.duration_chosen = true;
+            self.save_user_data();
+            return true;
+        }
+        self.log_debug(&format!("Rejected duration {}s (allowed {}-{}s)", duration, MIN_DURATION_SECONDS, MAX_DURATION_SECONDS));
+        false
     }
 
     #[wasm_bindgen]
-    pub fn start_countdown(&mut self) {
-        self.show_screen("countdown-screen");
-        self.app_state = AppState::Countdown;
-        self.countdown_value = 5;
-        self.update_countdown_display();
-        self.update_countdown_message();
+    pub fn set_duration_presets(&mut self, json: &str) -> bool {
+        let mut presets = match serde_json::from_str::<Vec<u32>>(json) {
+            Ok(presets) => presets,
+            Err(_) => return false,
+        };
+        
+        if presets.is_empty() || !presets.iter().all(|d| (MIN_DURATION_SECONDS..=MAX_DURATION_SECONDS).contains(d)) {
+            self.log_debug(&format!("Rejected duration presets: {:?}", presets));
+            return false;
+        }
+        
+        presets.sort_unstable();
+        presets.dedup();
+        self.user_data.duration_presets = presets;
+        self.save_user_data();
+        true
     }
 
     #[wasm_bindgen]
-    pub fn countdown_tick(&mut self) {
-        if self.countdown_value > 0 {
-            self.countdown_value -= 1;
-            self.update_countdown_display();
-            self.update_countdown_message();
-            
-            if self.countdown_value == 0 {
-                // Start game after showing "GO!" for a moment
-                console_log!("Countdown complete, starting game...");
-                self.start_game_session();
-            }
-        }
+    pub fn set_wpm_mode(&mut self, mode: WpmMode) {
+        self.user_data.wpm_mode = mode;
+        self.save_user_data();
     }
 
     #[wasm_bindgen]
-    pub fn start_game_session(&mut self) {
-        console_log!("Starting game session...");
-        clear_countdown_timer();
-        self.show_screen("game-screen");
-        self.app_state = AppState::Playing;
-        self.initialize_game();
-        self.generate_new_sentence();
-        self.focus_typing_input();
-        start_game_timer();
-        console_log!("Game session started, sentence: {}", self.current_sentence);
+    pub fn set_sentence_order(&mut self, order: SentenceOrder) {
+        self.user_data.sentence_order = order;
+        self.sequential_index = 0;
+        self.save_user_data();
     }
-    
-    fn focus_typing_input(&self) {
-        if let Some(window) = window() {
-            if let Some(document) = window.document() {
-                if let Some(input) = document.get_element_by_id("typing-input") {
-                    if let Ok(html_input) = input.dyn_into::<HtmlInputElement>() {
-                        html_input.focus().ok();
-                        console_log!("Focused typing input");
-                    }
-                }
-            }
+
+    #[wasm_bindgen]
+    pub fn set_max_wpm(&mut self, max_wpm: u32) -> bool {
+        if max_wpm >= 1 {
+            self.user_data.max_wpm = max_wpm;
+            self.save_user_data();
+            return true;
         }
+        false
     }
 
-    fn initialize_game(&mut self) {
-        self.session_start_time = Some(Date::now());
-        self.session_total_typed_chars = 0;
-        self.session_total_correct_chars = 0;
-        self.session_total_time_spent = 0.0;
-        self.session_sentences_completed = 0;
-        self.reset_current_sentence();
+    #[wasm_bindgen]
+    pub fn set_strict_mode(&mut self, enabled: bool) {
+        self.user_data.strict_mode = enabled;
+        self.save_user_data();
     }
 
-    fn reset_current_sentence(&mut self) {
-        self.start_time = None;
-        self.end_time = None;
-        self.typed_chars = 0;
-        self.correct_chars = 0;
-        self.is_active = false;
+    #[wasm_bindgen]
+    pub fn set_simplify_text(&mut self, enabled: bool) {
+        self.user_data.simplify_text = enabled;
+        self.save_user_data();
     }
 
     #[wasm_bindgen]
-    pub fn generate_new_sentence(&mut self) -> String {
-        let mut rng = SmallRng::from_entropy();
-        let language_sentences = self.sentences.get(&self.user_data.language)
-            .unwrap_or(self.sentences.get("id").unwrap());
-        
-        self.current_sentence = language_sentences.choose(&mut rng).unwrap().to_string();
-        self.reset_current_sentence();
-        self.display_sentence(&self.current_sentence.clone());
-        self.current_sentence.clone()
+    pub fn set_case_insensitive(&mut self, enabled: bool) {
+        self.user_data.case_insensitive = enabled;
+        self.save_user_data();
     }
 
     #[wasm_bindgen]
-    pub fn start_typing(&mut self) {
-        if !self.is_active {
-            self.start_time = Some(Date::now());
-            self.is_active = true;
-        }
+    pub fn set_trim_leading_space(&mut self, enabled: bool) {
+        self.user_data.trim_leading_space = enabled;
+        self.save_user_data();
     }
 
     #[wasm_bindgen]
-    pub fn update_typing_progress(&mut self, typed_text: &str) -> JsValue {
-        // Start typing if not already active
-        if !self.is_active {
-            console_log!("Starting typing session...");
-            self.start_typing();
-        }
+    pub fn set_letters_only(&mut self, enabled: bool) {
+        self.user_data.letters_only = enabled;
+        self.save_user_data();
+    }
 
-        self.typed_chars = typed_text.len();
-        self.correct_chars = 0;
-        
-        console_log!("Typed: '{}' ({} chars)", typed_text, self.typed_chars);
+    #[wasm_bindgen]
+    pub fn blacklist_sentence(&mut self, sentence: &str) {
+        self.user_data.blacklist.insert(sentence.to_string());
+        self.save_user_data();
+    }
 
-        let sentence_chars: Vec<char> = self.current_sentence.chars().collect();
-        let typed_chars: Vec<char> = typed_text.chars().collect();
+    #[wasm_bindgen]
+    pub fn clear_blacklist(&mut self) {
+        self.user_data.blacklist.clear();
+        self.save_user_data();
+    }
 
-        for (i, &typed_char) in typed_chars.iter().enumerate() {
-            if i < sentence_chars.len() && typed_char == sentence_chars[i] {
-                self.correct_chars += 1;
-            }
-        }
+    #[wasm_bindgen]
+    pub fn set_adaptive_selection(&mut self, enabled: bool) {
+        self.user_data.adaptive_selection = enabled;
+        self.save_user_data();
+    }
 
-        // Check if sentence is complete (user has typed enough characters)
-        let is_complete = typed_text.len() >= self.current_sentence.len();
-        
-        // Check if time expired
-        let time_expired = self.is_time_expired();
-        
-        if is_complete && self.is_active {
-            console_log!("Sentence completed: {}", typed_text);
-            self.complete_sentence();
-        } else if time_expired {
-            console_log!("Time expired, ending session");
-            self.end_session();
+    #[wasm_bindgen]
+    pub fn set_sentences_per_round(&mut self, count: u32) -> bool {
+        if (1..=MAX_SENTENCES_PER_ROUND).contains(&count) {
+            self.user_data.sentences_per_round = count;
+            self.save_user_data();
+            return true;
         }
-
-        self.create_progress_result(is_complete, time_expired)
+        false
     }
 
-    fn complete_sentence(&mut self) {
-        console_log!("Completing sentence, updating stats...");
-        self.end_time = Some(Date::now());
-        self.is_active = false;
-        
-        // Update session totals
-        if let (Some(start), Some(end)) = (self.start_time, self.end_time) {
-            let sentence_time = (end - start) / 1000.0;
-            self.session_total_time_spent += sentence_time;
-        }
-        
-        self.session_total_typed_chars += self.typed_chars;
-        self.session_total_correct_chars += self.correct_chars;
-        self.session_sentences_completed += 1;
-        
-        // Generate new sentence if time hasn't expired
-        if !self.is_time_expired() {
-            console_log!("Time remaining, generating new sentence...");
-            self.generate_new_sentence();
-            clear_typing_input();
-        } else {
-            console_log!("Time expired during sentence completion");
-            self.end_session();
+    #[wasm_bindgen]
+    pub fn set_sentence_target(&mut self, target: u32) -> bool {
+        if target <= MAX_SENTENCE_TARGET {
+            self.user_data.sentence_target = target;
+            self.save_user_data();
+            return true;
         }
+        false
     }
 
-    fn end_session(&mut self) {
-        self.is_active = false;
+    #[wasm_bindgen]
+    pub fn set_countdown_seconds(&mut self, seconds: u32) -> bool {
+        if (MIN_COUNTDOWN_SECONDS..=MAX_COUNTDOWN_SECONDS).contains(&seconds) {
+            self.user_data.countdown_seconds = seconds;
+            self.save_user_data();
+            return true;
+        }
+        false
+    }
+
+    #[wasm_bindgen]
+    pub fn set_kiosk_mode(&mut self, enabled: bool) {
+        self.user_data.kiosk_mode = enabled;
+        if !enabled {
+            self.cancel_kiosk_restart();
+        }
+        self.save_user_data();
+    }
+
+    #[wasm_bindgen]
+    pub fn set_kiosk_restart_delay(&mut self, delay_ms: u32) {
+        self.user_data.kiosk_restart_delay_ms = delay_ms;
+        self.save_user_data();
+    }
+
+    #[wasm_bindgen]
+    pub fn set_go_hold_ms(&mut self, hold_ms: u32) -> bool {
+        if hold_ms <= MAX_GO_HOLD_MS {
+            self.user_data.go_hold_ms = hold_ms;
+            self.save_user_data();
+            return true;
+        }
+        false
+    }
+
+    #[wasm_bindgen]
+    pub fn set_auto_advance_delay(&mut self, delay_ms: u32) {
+        self.user_data.auto_advance_delay_ms = delay_ms;
+        self.save_user_data();
+    }
+
+    #[wasm_bindgen]
+    pub fn set_target_wpm(&mut self, target_wpm: u32) {
+        self.user_data.target_wpm = target_wpm;
+        self.save_user_data();
+    }
+
+    #[wasm_bindgen]
+    pub fn set_keyboard_layout(&mut self, layout: &str) -> bool {
+        let layout = match layout {
+            "qwerty" => KeyboardLayout::Qwerty,
+            "azerty" => KeyboardLayout::Azerty,
+            "dvorak" => KeyboardLayout::Dvorak,
+            _ => return false,
+        };
+        self.user_data.keyboard_layout = layout;
+        self.save_user_data();
+        true
+    }
+
+    #[wasm_bindgen]
+    pub fn set_idle_timeout(&mut self, timeout_ms: u32) {
+        self.user_data.idle_timeout_ms = timeout_ms;
+        self.save_user_data();
+    }
+
+    #[wasm_bindgen]
+    pub fn set_error_sound_cooldown(&mut self, cooldown_ms: u32) {
+        self.user_data.error_sound_cooldown_ms = cooldown_ms;
+        self.save_user_data();
+    }
+
+    #[wasm_bindgen]
+    pub fn set_chars_per_word(&mut self, chars_per_word: f64) -> bool {
+        if chars_per_word.is_finite() && chars_per_word > 0.0 {
+            self.user_data.chars_per_word = chars_per_word;
+            self.save_user_data();
+            return true;
+        }
+        false
+    }
+
+    #[wasm_bindgen]
+    pub fn set_completion_threshold(&mut self, threshold: f64) -> bool {
+        if (0.5..=1.0).contains(&threshold) {
+            self.user_data.completion_threshold = threshold;
+            self.save_user_data();
+            return true;
+        }
+        false
+    }
+
+    #[wasm_bindgen]
+    pub fn goto_screen(&mut self, screen: &str) -> bool {
+        let state = match screen {
+            "welcome-screen" => AppState::Welcome,
+            "language-screen" => AppState::Language,
+            "timer-screen" => AppState::Timer,
+            "countdown-screen" => AppState::Countdown,
+            "game-screen" => AppState::Playing,
+            "pause-screen" => AppState::Paused,
+            "times-up-screen" => AppState::TimesUp,
+            "results-screen" => AppState::Results,
+            _ => return false,
+        };
+        
+        self.show_screen(screen);
+        self.set_state(state);
+        true
+    }
+
+    #[wasm_bindgen]
+    pub fn proceed_to_language(&mut self) {
+        if !self.user_data.name.is_empty() {
+            self.show_screen("language-screen");
+            self.set_state(AppState::Language);
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn proceed_to_timer(&mut self) {
+        self.show_screen("timer-screen");
+        self.set_state(AppState::Timer);
+    }
+
+    #[wasm_bindgen]
+    pub fn start_countdown(&mut self) {
+        self.show_screen("countdown-screen");
+        self.set_state(AppState::Countdown);
+        self.daily_challenge = false;
+        self.replay_queue.clear();
+        self.cancel_kiosk_restart();
+        self.cancel_go_hold();
+        self.countdown_value = self.user_data.countdown_seconds;
+        self.update_countdown_display();
+        self.update_countdown_message();
+    }
+
+    #[wasm_bindgen]
+    pub fn start_daily_challenge(&mut self) {
+        let seed = self.daily_seed();
+        self.log_debug(&format!("Starting daily challenge with seed {}", seed));
+        self.set_seed(seed);
+        self.start_countdown();
+        self.daily_challenge = true;
+    }
+
+    #[wasm_bindgen]
+    pub fn countdown_tick(&mut self) {
+        if self.countdown_value > 0 {
+            self.countdown_value -= 1;
+            self.update_countdown_display();
+            self.update_countdown_message();
+            
+            if self.countdown_value == 0 {
+                // Start game after showing "GO!" for a moment
+                self.log_debug("Countdown complete, starting game...");
+                clear_countdown_timer();
+                if self.user_data.go_hold_ms == 0 {
+                    self.start_game_session();
+                } else {
+                    let closure = self.go_closure
+                        .get_or_insert_with(|| Closure::wrap(Box::new(begin_game_session_hook) as Box<dyn FnMut()>));
+                    self.go_timeout_id = Some(setTimeout(closure, self.user_data.go_hold_ms));
+                }
+            }
+        }
+    }
+
+    // Leaving the countdown screen must not let a pending "GO!" start the game behind the player's back
+    fn cancel_go_hold(&mut self) {
+        clear_countdown_timer();
+        if let Some(timeout_id) = self.go_timeout_id.take() {
+            clearTimeout(timeout_id);
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn start_game_session(&mut self) {
+        self.log_debug("Starting game session...");
+        self.cancel_go_hold();
+        self.show_screen("game-screen");
+        self.set_state(AppState::Playing);
+        self.practice_mode = false;
+        self.zen_mode = false;
+        self.initialize_game();
+        self.generate_new_sentence();
+        self.focus_typing_input();
+        start_game_timer();
+        self.log_debug(&format!("Game session started, sentence: {}", self.current_sentence));
+    }
+    
+    #[wasm_bindgen]
+    pub fn start_practice(&mut self) {
+        self.log_debug("Starting practice session...");
+        self.show_screen("game-screen");
+        self.set_state(AppState::Practice);
+        self.practice_mode = true;
+        self.zen_mode = false;
+        self.replay_queue.clear();
+        self.initialize_game();
+        self.generate_new_sentence();
+        self.focus_typing_input();
+    }
+    
+    // Untimed and endless, but unlike practice the results still count
+    #[wasm_bindgen]
+    pub fn start_zen(&mut self) {
+        self.log_debug("Starting zen session...");
+        self.show_screen("game-screen");
+        self.set_state(AppState::Playing);
+        self.practice_mode = false;
+        self.zen_mode = true;
+        self.replay_queue.clear();
+        self.initialize_game();
+        self.generate_new_sentence();
+        self.focus_typing_input();
+        
+        if let Some(window) = window() {
+            if let Some(document) = window.document() {
+                if let Some(element) = document.get_element_by_id("timer") {
+                    element.set_text_content(Some("∞"));
+                }
+            }
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn stop_session(&mut self) {
+        self.finish_session();
+    }
+
+    #[wasm_bindgen]
+    pub fn finish_session(&mut self) -> bool {
+        let in_session = matches!(self.app_state, AppState::Playing | AppState::Practice | AppState::Paused);
+        if self.session_start_time.is_none() || !in_session {
+            return false;
+        }
+        if self.paused {
+            self.resume();
+        }
+        self.cancel_pending_advance();
+        clear_game_timer();
+        
+        // Fold the half-typed sentence into the totals so an early finish still reflects it
+        if self.is_active {
+            self.session_total_typed_chars += self.typed_chars;
+            self.session_total_correct_chars += self.correct_chars;
+            self.add_session_time(self.current_sentence_seconds());
+        }
+        self.end_session();
+        // Practice runs have no results screen to land on
+        if self.practice_mode {
+            self.change_settings();
+        }
+        true
+    }
+    
+    fn focus_typing_input(&self) {
+        if let Some(window) = window() {
+            if let Some(document) = window.document() {
+                if let Some(input) = document.get_element_by_id("typing-input") {
+                    if let Ok(html_input) = input.dyn_into::<HtmlInputElement>() {
+                        html_input.focus().ok();
+                        self.log_debug("Focused typing input");
+                    }
+                }
+            }
+        }
+    }
+
+    fn initialize_game(&mut self) {
+        self.session_start_time = Some(Date::now());
+        self.paused = false;
+        self.auto_paused = false;
+        self.pause_started_at = None;
+        self.paused_duration = 0.0;
+        self.last_activity_time = Some(Date::now());
+        self.session_total_typed_chars = 0;
+        self.session_total_correct_chars = 0;
+        self.session_total_time_spent = 0.0;
+        self.session_language_time.clear();
+        self.session_sentences_completed = 0;
+        self.session_sentences_skipped = 0;
+        self.session_words_typed = 0;
+        self.current_streak = 0;
+        self.sentence_results.clear();
+        self.keystroke_intervals.clear();
+        self.last_keystroke_time = None;
+        self.wpm_samples.clear();
+        self.row_mistakes.clear();
+        self.backspace_count = 0;
+        self.total_keystrokes = 0;
+        self.awaiting_next_sentence = false;
+        self.cancel_pending_advance();
+        self.clear_active_session();
+        // Forget the previous session's sentence so seeded sessions replay identically
+        self.current_sentence.clear();
+        self.original_sentence.clear();
+        self.sentence_pinned = false;
+        self.next_sentence = None;
+        self.played_sentences.clear();
+        self.sentence_bags.clear();
+        self.sequential_index = 0;
+        self.reset_current_sentence();
+    }
+
+    fn reset_current_sentence(&mut self) {
+        self.start_time = None;
+        self.end_time = None;
+        self.typed_chars = 0;
+        self.correct_chars = 0;
+        self.last_typed_len = 0;
+        self.blocked_at_index = None;
+        self.first_error_index = None;
+        self.overtyped_chars = 0;
+        self.last_typed_text.clear();
+        self.is_active = false;
+    }
+
+    #[wasm_bindgen]
+    pub fn generate_new_sentence(&mut self) -> String {
+        // A replayed session serves its recorded sentences before any random picks
+        let next = self.next_sentence.take();
+        let (language, sentence) = match (self.replay_queue.pop_front(), next) {
+            (Some(replayed), _) => replayed,
+            (None, Some(next)) => next,
+            (None, None) => self.pick_round(&self.original_sentence.clone()),
+        };
+        self.sentence_language = language.clone();
+        self.sentence_pinned = false;
+        self.awaiting_next_sentence = false;
+        self.original_sentence = sentence.clone();
+        self.current_sentence = if self.user_data.simplify_text {
+            Self::simplify_sentence(&sentence)
+        } else {
+            sentence.clone()
+        };
+        self.played_sentences.push((language, sentence));
+        
+        // Roll the following sentence up front so the UI can preview it
+        self.next_sentence = match self.replay_queue.front() {
+            Some(replayed) => Some(replayed.clone()),
+            None => Some(self.pick_round(&self.original_sentence.clone())),
+        };
+        
+        self.reset_current_sentence();
+        self.display_sentence(&self.current_sentence.clone());
+        self.current_sentence.clone()
+    }
+
+    // Tutorials pin an exact sentence instead of drawing from the pool
+    #[wasm_bindgen]
+    pub fn set_current_sentence(&mut self, text: &str) -> bool {
+        if text.trim().is_empty() {
+            return false;
+        }
+        
+        // A celebration timer left running would replace the pinned sentence when it fires
+        self.cancel_pending_advance();
+        self.awaiting_next_sentence = false;
+        self.sentence_language = self.user_data.language.clone();
+        self.original_sentence = text.to_string();
+        self.current_sentence = text.to_string();
+        self.sentence_pinned = true;
+        self.reset_current_sentence();
+        self.display_sentence(text);
+        true
+    }
+
+    // Lowercase letters, digits and single spaces only, for beginning readers
+    fn simplify_sentence(sentence: &str) -> String {
+        let stripped: String = sentence.chars()
+            .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+            .flat_map(|c| c.to_lowercase())
+            .collect();
+        stripped.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
+    // Older kids can get several sentences joined into one paragraph-length target
+    fn pick_round(&mut self, previous: &str) -> (String, String) {
+        let (language, first) = self.pick_sentence(previous);
+        let mut texts = vec![first];
+        
+        for _ in 1..self.user_data.sentences_per_round.max(1) {
+            let last = texts[texts.len() - 1].clone();
+            texts.push(self.pick_sentence(&last).1);
+        }
+        (language, texts.join(" "))
+    }
+
+    // Returns (language code, sentence)
+    fn pick_sentence(&mut self, previous: &str) -> (String, String) {
+        // Mixed-language sessions draw the language first, then a sentence from its pool
+        let language = if self.user_data.languages.len() > 1 {
+            self.user_data.languages.choose(&mut self.rng).unwrap().clone()
+        } else {
+            self.user_data.language.clone()
+        };
+        
+        let Some(language) = self.resolve_sentence_language(language) else {
+            self.log_debug("No sentences available in any language");
+            return (String::new(), String::new());
+        };
+        let language_sentences = &self.sentences[&language];
+        
+        // Blacklisted sentences are dropped unless that would empty the pool
+        let allowed: Vec<&SentenceEntry> = language_sentences.iter()
+            .filter(|entry| !self.user_data.blacklist.contains(entry.text))
+            .collect();
+        let entries = if allowed.is_empty() { language_sentences.iter().collect() } else { allowed };
+        let all_texts: Vec<&'static str> = entries.iter().map(|entry| entry.text).collect();
+        
+        // Narrow to the chosen category, falling back to the whole pool when nothing matches
+        let category = &self.user_data.category;
+        let in_category: Vec<&'static str> = entries.iter()
+            .filter(|entry| category.is_empty() || entry.category == category.as_str())
+            .map(|entry| entry.text)
+            .collect();
+        let texts = if in_category.is_empty() { all_texts } else { in_category };
+        
+        // Lowercase warm-up keeps to sentences without capitals, topped up with the warm-up
+        // sentences; pools with neither fall back to their capitalised sentences
+        let texts = if self.user_data.letters_only {
+            let lowercase: Vec<&'static str> = texts.iter()
+                .copied()
+                .chain(warm_up_sentences(&language).iter().copied())
+                .filter(|sentence| !sentence.chars().any(char::is_uppercase))
+                .filter(|sentence| !self.user_data.blacklist.contains(*sentence))
+                .collect();
+            if lowercase.is_empty() { texts } else { lowercase }
+        } else {
+            texts
+        };
+        
+        // Curriculum order walks the pool front to back, wrapping at the end
+        if self.user_data.sentence_order == SentenceOrder::Sequential {
+            let sentence = texts[self.sequential_index % texts.len()].to_string();
+            self.sequential_index += 1;
+            return (language, sentence);
+        }
+        
+        // Leave out the previous sentence so small pools don't repeat back-to-back
+        let candidates: Vec<&str> = texts.iter()
+            .copied()
+            .filter(|sentence| *sentence != previous)
+            .collect();
+        let pool = if candidates.is_empty() { texts.clone() } else { candidates };
+        
+        // Adaptive drills lean toward sentences with characters the player keeps missing
+        let sentence = if self.user_data.adaptive_selection && !self.user_data.mistake_counts.is_empty() {
+            let weights: Vec<u32> = pool.iter().map(|sentence| self.mistake_weight(sentence)).collect();
+            let index = rand::distributions::WeightedIndex::new(&weights)
+                .map(|dist| dist.sample(&mut self.rng))
+                .unwrap_or(0);
+            pool[index].to_string()
+        } else {
+            self.draw_from_bag(&language, &texts, &pool).to_string()
+        };
+        (language, sentence)
+    }
+
+    // Missing pools fall back to English, then to any language that has sentences
+    fn resolve_sentence_language(&self, language: String) -> Option<String> {
+        let has_sentences = |code: &str| self.sentences.get(code).is_some_and(|pool| !pool.is_empty());
+        if has_sentences(&language) {
+            return Some(language);
+        }
+        
+        let mut codes: Vec<&String> = self.sentences.keys().collect();
+        codes.sort();
+        let fallback = if has_sentences("en") {
+            Some("en".to_string())
+        } else {
+            codes.into_iter().find(|code| has_sentences(code)).cloned()
+        };
+        if let Some(fallback) = &fallback {
+            self.log_debug(&format!("Warning: no sentences for '{}', falling back to '{}'", language, fallback));
+        }
+        fallback
+    }
+
+    // Shuffle bag per language: nothing repeats until every eligible sentence has had a turn
+    fn draw_from_bag(&mut self, language: &str, texts: &[&'static str], pool: &[&str]) -> &'static str {
+        let bag = self.sentence_bags.entry(language.to_string()).or_default();
+        // Settings such as the category may have narrowed the pool since the bag was filled
+        bag.retain(|text| texts.contains(text));
+        
+        if bag.iter().all(|text| !pool.contains(text)) {
+            let mut refill = texts.to_vec();
+            refill.shuffle(&mut self.rng);
+            *bag = refill.into();
+        }
+        
+        let index = bag.iter().position(|text| pool.contains(text)).unwrap_or(0);
+        bag.remove(index).unwrap_or(texts[0])
+    }
+
+    #[wasm_bindgen]
+    pub fn available_languages(&self) -> JsValue {
+        let mut languages: Vec<LanguageInfo> = self.sentences.keys()
+            .map(|code| LanguageInfo {
+                code: code.clone(),
+                name: Self::language_display_name(code).to_string(),
+                practice_set: Self::is_practice_set(code),
+            })
+            .collect();
+        languages.sort_by(|a, b| a.code.cmp(&b.code));
+        
+        serde_wasm_bindgen::to_value(&languages).unwrap_or(JsValue::NULL)
+    }
+
+    fn language_display_name(lang_code: &str) -> &str {
+        match lang_code {
+            "id" => "Bahasa Indonesia",
+            "merdeka" => "Hari Kemerdekaan",
+            "en" => "English",
+            "es" => "Español",
+            "fr" => "Français",
+            "ar" => "العربية",
+            "zh" => "中文",
+            "numbers" => "Numbers",
+            "symbols" => "Symbols",
+            other => other,
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn set_category(&mut self, category: &str) {
+        self.user_data.category = category.to_string();
+        self.save_user_data();
+    }
+
+    #[wasm_bindgen]
+    pub fn categories_for(&self, lang_code: &str) -> JsValue {
+        let mut categories: Vec<&str> = self.sentences.get(lang_code)
+            .map(|sentences| sentences.iter().map(|entry| entry.category).collect())
+            .unwrap_or_default();
+        categories.sort();
+        categories.dedup();
+        
+        serde_wasm_bindgen::to_value(&categories).unwrap_or(JsValue::NULL)
+    }
+
+    #[wasm_bindgen]
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = SmallRng::seed_from_u64(seed);
+    }
+
+    #[wasm_bindgen]
+    pub fn sentences_for(&self, lang_code: &str) -> JsValue {
+        let pool: Vec<String> = self.sentences.get(lang_code)
+            .map(|sentences| sentences.iter().map(|entry| entry.text.to_string()).collect())
+            .unwrap_or_default();
+
+        serde_wasm_bindgen::to_value(&pool).unwrap_or(JsValue::NULL)
+    }
+
+    #[wasm_bindgen]
+    pub fn sentence_progress(&self, typed_text: &str) -> f64 {
+        let sentence_len = graphemes(&self.current_sentence).len();
+        if sentence_len == 0 {
+            return 0.0;
+        }
+        
+        ((graphemes(typed_text).len() as f64) / (sentence_len as f64)).clamp(0.0, 1.0)
+    }
+
+    #[wasm_bindgen]
+    pub fn char_combo(&self, typed_text: &str) -> u32 {
+        self.correct_prefix_len(typed_text) as u32
+    }
+
+    #[wasm_bindgen]
+    pub fn current_accuracy(&self, typed_text: &str) -> f64 {
+        let typed = graphemes(typed_text);
+        let correct = graphemes(&self.current_sentence).into_iter()
+            .zip(typed.iter())
+            .filter(|(expected, typed)| self.units_match(expected, typed))
+            .count();
+        Self::calculate_accuracy(correct, typed.len())
+    }
+
+    #[wasm_bindgen]
+    pub fn sentence_breakdown(&self) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.sentence_results).unwrap_or(JsValue::NULL)
+    }
+
+    #[wasm_bindgen]
+    pub fn remaining_text(&self, typed_text: &str) -> String {
+        let matched = self.correct_prefix_len(typed_text);
+        graphemes(&self.current_sentence)[matched..].concat()
+    }
+
+    #[wasm_bindgen]
+    pub fn is_valid_prefix(&self, typed_text: &str) -> bool {
+        let typed_len = graphemes(typed_text).len();
+        typed_len <= graphemes(&self.current_sentence).len()
+            && self.correct_prefix_len(typed_text) == typed_len
+    }
+
+    // Number of leading characters typed before the first mismatch
+    fn correct_prefix_len(&self, typed_text: &str) -> usize {
+        self.current_sentence.graphemes(true)
+            .zip(typed_text.graphemes(true))
+            .take_while(|(expected, typed)| self.units_match(expected, typed))
+            .count()
+    }
+
+    // Beginners may opt out of being marked wrong for capitalisation
+    fn units_match(&self, expected: &str, typed: &str) -> bool {
+        if self.user_data.case_insensitive {
+            expected.to_lowercase() == typed.to_lowercase()
+        } else {
+            expected == typed
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn start_typing(&mut self) {
+        if !self.is_active {
+            self.start_time = Some(Date::now());
+            self.is_active = true;
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn update_typing_progress(&mut self, typed_text: &str) -> JsValue {
+        // An idle auto-pause ends as soon as the player types again
+        if self.paused && self.auto_paused {
+            self.resume();
+        }
+
+        // Stray keystrokes during a pause must not start timing or count characters
+        if self.paused {
+            return self.create_progress_result(false, false);
+        }
+        self.last_activity_time = Some(Date::now());
+
+        // Ignore input while the "Great job!" pause is on screen or the embedder owes a sentence
+        if self.awaiting_advance || self.awaiting_next_sentence {
+            return self.create_progress_result(false, false);
+        }
+
+        // Stray spaces before the first real character neither start the clock nor count as typos
+        let typed_text = if self.user_data.trim_leading_space && !self.current_sentence.starts_with(' ') {
+            typed_text.trim_start_matches(' ')
+        } else {
+            typed_text
+        };
+        if typed_text.is_empty() && !self.is_active {
+            return self.create_progress_result(false, false);
+        }
+
+        // Repeat events for unchanged input (modifier keys, arrows) must not skew timing or counts
+        if self.is_active && typed_text == self.last_typed_text {
+            // Running out of time still ends the session, exactly as a new keystroke would
+            let time_expired = self.is_time_expired();
+            if time_expired {
+                self.end_session();
+            }
+            return self.create_progress_result(false, time_expired);
+        }
+        self.last_typed_text = typed_text.to_string();
+
+        // Start typing if not already active
+        if !self.is_active {
+            self.log_debug("Starting typing session...");
+            self.start_typing();
+        }
+
+        let now = Date::now();
+        if let Some(last) = self.last_keystroke_time {
+            self.keystroke_intervals.push(now - last);
+        }
+        self.last_keystroke_time = Some(now);
+        self.total_keystrokes += 1;
+
+        let sentence = self.current_sentence.clone();
+        let sentence_units = graphemes(&sentence);
+        let typed_units = graphemes(typed_text);
+        self.typed_chars = typed_units.len();
+        self.correct_chars = 0;
+        
+        // A shorter input than last time means the player backtracked
+        if self.typed_chars < self.last_typed_len {
+            self.backspace_count += 1;
+        }
+        
+        self.log_debug(&format!("Typed: '{}' ({} chars)", typed_text, self.typed_chars));
+
+        for (i, &typed_unit) in typed_units.iter().enumerate() {
+            if i < sentence_units.len() && self.units_match(sentence_units[i], typed_unit) {
+                self.correct_chars += 1;
+            }
+        }
+        // Anything past the end of the sentence is counted as typed but never correct
+        self.overtyped_chars = self.typed_chars.saturating_sub(sentence_units.len());
+
+        // Only the newest keystroke gets a sound, and only when a character was added
+        if self.typed_chars > self.last_typed_len {
+            let index = self.typed_chars - 1;
+            let is_correct = match (sentence_units.get(index), typed_units.get(index)) {
+                (Some(expected), Some(typed)) => self.units_match(expected, typed),
+                _ => false,
+            };
+            self.play_keystroke_sound(is_correct);
+            if !is_correct {
+                if let Some(expected) = sentence_units.get(index).and_then(|unit| unit.chars().next()) {
+                    self.record_row_mistake(expected);
+                    *self.user_data.mistake_counts.entry(Self::mistake_key(expected)).or_insert(0) += 1;
+                }
+            }
+        }
+        self.last_typed_len = self.typed_chars;
+
+        self.wpm_samples.push(self.calculate_current_wpm());
+        if self.wpm_samples.len() > MAX_WPM_SAMPLES {
+            self.wpm_samples.remove(0);
+        }
+
+        self.first_error_index = Some(self.correct_prefix_len(typed_text)).filter(|&index| index < self.typed_chars);
+
+        // In strict mode the first typo blocks progress until it is fixed; a correct sentence
+        // with extra characters after it has no typo to fix
+        self.blocked_at_index = if self.user_data.strict_mode {
+            self.first_error_index.filter(|&index| index < sentence_units.len())
+        } else {
+            None
+        };
+
+        // Check if sentence is complete (user has typed enough characters)
+        let required_chars = (self.user_data.completion_threshold * sentence_units.len() as f64).ceil() as usize;
+        let is_complete = self.typed_chars >= required_chars && self.blocked_at_index.is_none();
+        
+        // Check if time expired
+        let time_expired = self.is_time_expired();
+        
+        if is_complete && self.is_active {
+            self.log_debug(&format!("Sentence completed: {}", typed_text));
+            self.complete_sentence(typed_text);
+        } else if time_expired {
+            self.log_debug("Time expired, ending session");
+            self.end_session();
+        }
+
+        self.create_progress_result(is_complete, time_expired)
+    }
+
+    // Mistakes are filed under the row of the key the player should have pressed
+    fn record_row_mistake(&mut self, expected: char) {
+        let row = Self::keyboard_row(self.user_data.keyboard_layout, expected);
+        *self.row_mistakes.entry(row.to_string()).or_insert(0) += 1;
+    }
+
+    fn mistake_key(c: char) -> char {
+        c.to_lowercase().next().unwrap_or(c)
+    }
+
+    // Sentences score one plus the mistakes logged against each distinct character they contain
+    fn mistake_weight(&self, sentence: &str) -> u32 {
+        let mut seen: Vec<char> = Vec::new();
+        let mut weight = 1;
+        for c in sentence.chars().map(Self::mistake_key) {
+            if !seen.contains(&c) {
+                seen.push(c);
+                weight += self.user_data.mistake_counts.get(&c).copied().unwrap_or(0);
+            }
+        }
+        weight
+    }
+
+    fn keyboard_row(layout: KeyboardLayout, c: char) -> &'static str {
+        if c == ' ' {
+            return "space";
+        }
+        
+        let key = c.to_lowercase().next().unwrap_or(c);
+        Self::layout_rows(layout).iter()
+            .find(|(_, keys)| keys.contains(key))
+            .map_or("other", |(row, _)| *row)
+    }
+
+    fn layout_rows(layout: KeyboardLayout) -> [(&'static str, &'static str); 4] {
+        match layout {
+            KeyboardLayout::Qwerty => [
+                ("number", "`1234567890-=~!@#$%^&*()_+"),
+                ("top", "qwertyuiop[]\\{}|"),
+                ("home", "asdfghjkl;':\""),
+                ("bottom", "zxcvbnm,./<>?"),
+            ],
+            KeyboardLayout::Azerty => [
+                ("number", "²&é\"'(-è_çà)=1234567890°+"),
+                ("top", "azertyuiop^$¨£"),
+                ("home", "qsdfghjklmù*%µ"),
+                ("bottom", "<>wxcvbn,;:!?./§"),
+            ],
+            KeyboardLayout::Dvorak => [
+                ("number", "`1234567890[]~!@#$%^&*(){}"),
+                ("top", "',.pyfgcrl/=\"<>?+\\|"),
+                ("home", "aoeuidhtns-_"),
+                ("bottom", ";qjkxbmwvz:"),
+            ],
+        }
+    }
+
+    fn play_keystroke_sound(&mut self, is_correct: bool) {
+        // Bursts of mistakes share one error sound per cooldown window
+        if !is_correct {
+            let now = Date::now();
+            let cooldown = self.user_data.error_sound_cooldown_ms as f64;
+            if self.last_error_sound_time.is_some_and(|last| now - last < cooldown) {
+                return;
+            }
+            self.last_error_sound_time = Some(now);
+        }
+        
+        let callback = if is_correct { &self.correct_sound_callback } else { &self.error_sound_callback };
+        if let Some(callback) = callback {
+            callback.call0(&JsValue::NULL).ok();
+        }
+    }
+
+    fn complete_sentence(&mut self, typed_text: &str) {
+        self.log_debug("Completing sentence, updating stats...");
+        self.end_time = Some(Date::now());
+        self.is_active = false;
+        
+        // Update session totals
+        let mut sentence_time = match (self.start_time, self.end_time) {
+            (Some(start), Some(end)) => (end - start) / 1000.0,
+            _ => 0.0,
+        };
+        // A sentence finished after the buzzer only counts up to the configured duration
+        if !self.is_untimed() {
+            let time_left = (self.user_data.duration as f64 - self.session_total_time_spent).max(0.0);
+            sentence_time = sentence_time.min(time_left);
+        }
+        self.add_session_time(sentence_time);
+        
+        self.sentence_results.push(SentenceResult {
+            sentence: self.original_sentence.clone(),
+            wpm: self.calculate_wpm(self.typed_chars, self.correct_chars, sentence_time) as u32,
+            accuracy: Self::calculate_accuracy(self.correct_chars, self.typed_chars) as u32,
+            time_spent: sentence_time,
+        });
+        
+        self.session_total_typed_chars += self.typed_chars;
+        self.session_total_correct_chars += self.correct_chars;
+        self.session_sentences_completed += 1;
+        self.session_words_typed += typed_text.split_whitespace().count();
+        self.record_completed_sentences();
+        self.update_streak();
+        
+        let target = self.user_data.sentence_target as usize;
+        let target_reached = target > 0 && !self.is_untimed() && self.session_sentences_completed >= target;
+        
+        // Generate new sentence if time hasn't expired
+        if target_reached {
+            self.log_debug("Sentence target reached");
+            self.end_session();
+        } else if !self.is_time_expired() {
+            if !self.is_untimed() {
+                self.save_active_session();
+            }
+            if !self.auto_generate {
+                // The embedder supplies the next sentence through set_current_sentence
+                self.awaiting_next_sentence = true;
+                self.reset_current_sentence();
+            } else if self.user_data.auto_advance_delay_ms > 0 {
+                self.schedule_sentence_advance();
+            } else {
+                self.log_debug("Time remaining, generating new sentence...");
+                self.generate_new_sentence();
+                clear_typing_input();
+            }
+        } else {
+            self.log_debug("Time expired during sentence completion");
+            self.end_session();
+        }
+    }
+
+    // Mixed-language sessions credit each sentence's time to the language it was drawn from
+    fn add_session_time(&mut self, seconds: f64) {
+        self.session_total_time_spent += seconds;
+        *self.session_language_time.entry(self.sentence_language.clone()).or_insert(0.0) += seconds;
+    }
+
+    // Rounds join several pool sentences, so each one that makes up the finished text is credited
+    fn record_completed_sentences(&mut self) {
+        if self.practice_mode || self.sentence_pinned {
+            return;
+        }
+        
+        let round = &self.original_sentence;
+        let finished: Vec<String> = self.sentences.values()
+            .flatten()
+            .map(|entry| entry.text)
+            .filter(|text| {
+                round == text
+                    || round.starts_with(&format!("{} ", text))
+                    || round.ends_with(&format!(" {}", text))
+                    || round.contains(&format!(" {} ", text))
+            })
+            .map(str::to_string)
+            .collect();
+        
+        let mut added = false;
+        for text in finished {
+            added |= self.user_data.completed_sentences.insert(text);
+        }
+        if added {
+            self.save_user_data();
+        }
+    }
+
+    fn update_streak(&mut self) {
+        if self.typed_chars > 0 && self.correct_chars == self.typed_chars {
+            self.current_streak += 1;
+        } else {
+            self.current_streak = 0;
+        }
+        
+        if !self.practice_mode && self.current_streak > self.user_data.best_streak {
+            self.user_data.best_streak = self.current_streak;
+        }
+    }
+
+    fn schedule_sentence_advance(&mut self) {
+        self.log_debug(&format!("Showing celebration for {}ms before next sentence", self.user_data.auto_advance_delay_ms));
+        self.awaiting_advance = true;
+        self.display_celebration();
+        
+        // One closure serves every sentence instead of leaking a new one each time
+        let closure = self.advance_closure
+            .get_or_insert_with(|| Closure::wrap(Box::new(advance_sentence_hook) as Box<dyn FnMut()>));
+        self.advance_timeout_id = Some(setTimeout(closure, self.user_data.auto_advance_delay_ms));
+    }
+
+    fn cancel_pending_advance(&mut self) {
+        if let Some(timeout_id) = self.advance_timeout_id.take() {
+            clearTimeout(timeout_id);
+        }
+        self.awaiting_advance = false;
+    }
+
+    #[wasm_bindgen]
+    pub fn advance_sentence(&mut self) {
+        if !self.awaiting_advance {
+            return;
+        }
+        // Also covers an early call from the embedder while the timer is still pending
+        self.cancel_pending_advance();
+        
+        if self.is_time_expired() {
+            self.log_debug("Time expired during celebration");
+            self.end_session();
+        } else {
+            self.generate_new_sentence();
+            clear_typing_input();
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn pause(&mut self) {
+        if self.paused || !matches!(self.app_state, AppState::Playing | AppState::Practice) {
+            return;
+        }
+        self.begin_pause();
+        clear_game_timer();
+        self.show_screen("pause-screen");
+        self.set_state(AppState::Paused);
+    }
+
+    fn begin_pause(&mut self) {
+        self.paused = true;
+        self.pause_started_at = Some(Date::now());
+        self.log_debug("Session paused");
+    }
+
+    #[wasm_bindgen]
+    pub fn resume(&mut self) {
+        if !self.paused {
+            return;
+        }
+        
+        if let Some(paused_at) = self.pause_started_at.take() {
+            let pause_length = Date::now() - paused_at;
+            self.paused_duration += pause_length;
+            // Shift the sentence clock too so the pause doesn't drag down its WPM
+            if let Some(start) = self.start_time {
+                self.start_time = Some(start + pause_length);
+            }
+        }
+        self.paused = false;
+        self.auto_paused = false;
+        self.last_keystroke_time = None;
+        self.last_activity_time = Some(Date::now());
+        self.log_debug("Session resumed");
+        
+        if self.app_state == AppState::Paused {
+            self.show_screen("game-screen");
+            self.set_state(if self.practice_mode { AppState::Practice } else { AppState::Playing });
+            self.focus_typing_input();
+            // Practice and zen runs never started the countdown timer
+            if !self.is_untimed() {
+                start_game_timer();
+            }
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn check_idle(&mut self) -> bool {
+        let timeout = self.user_data.idle_timeout_ms;
+        if timeout == 0 || self.paused || !matches!(self.app_state, AppState::Playing) {
+            return false;
+        }
+        
+        let Some(last_activity) = self.last_activity_time else {
+            return false;
+        };
+        
+        if Date::now() - last_activity >= timeout as f64 {
+            self.log_debug(&format!("No keystrokes for {}ms, auto-pausing", timeout));
+            // Idle pauses stay on the game screen so the next keystroke can pick up again
+            self.begin_pause();
+            self.auto_paused = true;
+            return true;
+        }
+        false
+    }
+
+    #[wasm_bindgen]
+    pub fn skip_sentence(&mut self) {
+        if !matches!(self.app_state, AppState::Playing | AppState::Practice) {
+            return;
+        }
+        self.log_debug(&format!("Skipping sentence: {}", self.current_sentence));
+        // During the celebration the sentence is already finished, so skipping only moves on early
+        if !self.awaiting_advance {
+            self.session_sentences_skipped += 1;
+        }
+        self.cancel_pending_advance();
+        self.generate_new_sentence();
+        clear_typing_input();
+    }
+
+    fn end_session(&mut self) {
+        // Time ran out while a sentence was still being typed
+        let ended_incomplete = self.is_active;
+        self.is_active = false;
+        self.clear_active_session();
+        
+        // Practice runs are a warmup and never touch saved stats
+        if self.practice_mode {
+            self.log_debug("Practice session ended, stats unchanged");
+            return;
+        }
+        
+        // Calculate final results
+        let wpm = self.calculate_session_wpm();
+        let accuracy = self.calculate_session_accuracy();
+        
+        // Compare against the previous bests before they are overwritten
+        let new_wpm_record = wpm as u32 > self.user_data.best_wpm;
+        // A handful of lucky keystrokes shouldn't count as a best accuracy
+        let new_accuracy_record = self.session_total_typed_chars > MIN_CHARS_FOR_BEST_ACCURACY
+            && accuracy as u32 > self.user_data.best_accuracy;
+        
+        self.session_result = Some(SessionResult {
+            wpm: wpm as u32,
+            accuracy: accuracy as u32,
+            typed_chars: self.session_total_typed_chars as u32,
+            correct_chars: self.session_total_correct_chars as u32,
+            time_spent: self.session_total_time_spent,
+            sentences_completed: self.session_sentences_completed as u32,
+            new_wpm_record,
+            new_accuracy_record,
+            daily_challenge: self.daily_challenge,
+            goal_met: wpm as u32 >= self.user_data.target_wpm,
+            is_cpm: self.uses_character_metrics(),
+            has_previous_session: self.user_data.last_wpm.is_some(),
+            wpm_delta: self.user_data.last_wpm.map(|last| wpm as i32 - last as i32),
+            accuracy_delta: self.user_data.last_accuracy.map(|last| accuracy as i32 - last as i32),
+            adjusted_wpm: self.calculate_adjusted_wpm() as u32,
+            ended_incomplete,
+            words_typed: self.session_words_typed as u32,
+        });
+        
+        // Update user data
+        self.user_data.total_sessions += 1;
+        self.user_data.last_wpm = Some(wpm as u32);
+        self.user_data.last_accuracy = Some(accuracy as u32);
+        // Time restored from a reloaded session has no per-language split, so it goes to the main language
+        let attributed: f64 = self.session_language_time.values().sum();
+        *self.user_data.time_per_language
+            .entry(self.user_data.language.clone())
+            .or_insert(0.0) += (self.session_total_time_spent - attributed).max(0.0);
+        for (language, seconds) in &self.session_language_time {
+            *self.user_data.time_per_language.entry(language.clone()).or_insert(0.0) += seconds;
+        }
+        if new_wpm_record {
+            self.user_data.best_wpm = wpm as u32;
+        }
+        if new_accuracy_record {
+            self.user_data.best_accuracy = accuracy as u32;
+        }
+        if let Some(result) = &self.session_result {
+            self.user_data.session_history.push(result.clone());
+            if self.user_data.session_history.len() > MAX_SESSION_HISTORY {
+                self.user_data.session_history.remove(0);
+            }
+        }
+        
+        self.save_user_data();
+        
+        self.record_leaderboard_entry(LeaderboardEntry {
+            name: self.user_data.name.clone(),
+            wpm: wpm as u32,
+            accuracy: accuracy as u32,
+            timestamp: Date::now(),
+        });
+        
+        if let (Some(callback), Some(result)) = (&self.session_complete_callback, &self.session_result) {
+            if let Ok(value) = serde_wasm_bindgen::to_value(result) {
+                callback.call1(&JsValue::NULL, &value).ok();
+            }
+        }
+        
+        self.log_debug(&format!("Session ended, showing results directly. WPM: {}, Accuracy: {}%", wpm as u32, accuracy as u32));
+        
+        // Show results directly without time's up screen
+        self.show_results();
+    }
+
+    #[wasm_bindgen]
+    pub fn show_results(&mut self) {
+        self.log_debug("show_results called, transitioning to results screen");
+        self.set_state(AppState::Results);
+        self.show_screen("results-screen");
+        self.display_results();
+        self.log_debug("Results screen displayed successfully");
+        
+        // Kiosks loop back into a fresh round once the results have been on screen a while
+        if self.user_data.kiosk_mode {
+            self.cancel_kiosk_restart();
+            let closure = self.kiosk_closure
+                .get_or_insert_with(|| Closure::wrap(Box::new(kiosk_restart_hook) as Box<dyn FnMut()>));
+            self.kiosk_timeout_id = Some(setTimeout(closure, self.user_data.kiosk_restart_delay_ms));
+        }
+    }
+
+    fn cancel_kiosk_restart(&mut self) {
+        if let Some(timeout_id) = self.kiosk_timeout_id.take() {
+            clearTimeout(timeout_id);
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn restart_game(&mut self) {
+        self.start_countdown();
+    }
+
+    #[wasm_bindgen]
+    pub fn replay_session(&mut self) {
+        let played: VecDeque<(String, String)> = self.played_sentences.iter().cloned().collect();
+        self.log_debug(&format!("Replaying {} sentences from the last session", played.len()));
+        self.start_countdown();
+        self.replay_queue = played;
+    }
+
+    #[wasm_bindgen]
+    pub fn change_settings(&mut self) {
+        self.cancel_kiosk_restart();
+        self.cancel_go_hold();
+        self.show_screen("language-screen");
+        self.set_state(AppState::Language);
+    }
+
+    #[wasm_bindgen]
+    pub fn new_session(&mut self) {
+        self.cancel_kiosk_restart();
+        self.cancel_go_hold();
+        self.show_screen("welcome-screen");
+        self.set_state(AppState::Welcome);
+    }
+
+    #[wasm_bindgen]
+    pub fn reset_stats(&mut self) {
+        self.user_data.best_wpm = 0;
+        self.user_data.best_accuracy = 0;
+        self.user_data.total_sessions = 0;
+        self.user_data.best_streak = 0;
+        self.user_data.last_wpm = None;
+        self.user_data.last_accuracy = None;
+        self.user_data.session_history.clear();
+        self.session_result = None;
+        self.save_user_data();
+        self.log_debug("Player stats reset");
+    }
+
+    #[wasm_bindgen]
+    pub fn reset_all(&mut self) {
+        self.user_data = UserData::default();
+        self.session_result = None;
+        self.leaderboard.clear();
+        self.profiles.clear();
+        self.active_profile.clear();
+        self.clear_all_stored_data();
+        self.log_debug("All player data reset");
+    }
+
+    #[wasm_bindgen]
+    pub fn list_profiles(&self) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.profiles).unwrap_or(JsValue::NULL)
+    }
+
+    #[wasm_bindgen]
+    pub fn create_profile(&mut self, name: &str) -> bool {
+        if self.profiles.is_empty() && self.active_profile.is_empty() {
+            self.migrate_legacy_profile();
+        }
+        
+        let name = name.trim();
+        if name.len() < 2 || self.profiles.iter().any(|profile| profile == name) {
+            return false;
+        }
+        
+        self.profiles.push(name.to_string());
+        self.save_profiles();
+        self.switch_profile(name)
+    }
+
+    // Players from before profiles existed live under the unnamed key; give them a named
+    // profile so they can be switched back to once someone else is added
+    fn migrate_legacy_profile(&mut self) {
+        let legacy_name = self.user_data.name.trim().to_string();
+        if legacy_name.len() < 2 {
+            return;
+        }
+        
+        self.clear_stored_user_data();
+        self.clear_active_session();
+        self.profiles.push(legacy_name.clone());
+        self.active_profile = legacy_name;
+        self.save_user_data();
+        self.save_profiles();
+        self.log_debug(&format!("Moved the existing player into profile '{}'", self.active_profile));
+    }
+
+    #[wasm_bindgen]
+    pub fn switch_profile(&mut self, name: &str) -> bool {
+        if !self.profiles.iter().any(|profile| profile == name) {
+            return false;
+        }
+        
+        // Stash the outgoing player's progress before loading the next one
+        self.save_user_data();
+        self.active_profile = name.to_string();
+        self.save_profiles();
+        
+        self.user_data = UserData::default();
+        self.session_result = None;
+        self.load_user_data();
+        if self.user_data.name.is_empty() {
+            self.user_data.name = name.to_string();
+            self.save_user_data();
+        }
+        self.update_personalization();
+        self.log_debug(&format!("Switched to profile '{}'", name));
+        true
+    }
+
+    // Share of past games slower than the given speed; the leaderboard stands in until there is history
+    #[wasm_bindgen]
+    pub fn wpm_percentile(&self, wpm: u32) -> f64 {
+        let past: Vec<u32> = if self.user_data.session_history.is_empty() {
+            self.leaderboard.iter().map(|entry| entry.wpm).collect()
+        } else {
+            self.user_data.session_history.iter().map(|result| result.wpm).collect()
+        };
+        if past.is_empty() {
+            return 100.0;
+        }
+        
+        let slower = past.iter().filter(|&&past_wpm| past_wpm < wpm).count();
+        slower as f64 / past.len() as f64 * 100.0
+    }
+
+    #[wasm_bindgen]
+    pub fn export_history_csv(&self) -> String {
+        let mut csv = String::from("wpm,accuracy,typed_chars,correct_chars,time_spent,sentences_completed\n");
+        for result in &self.user_data.session_history {
+            let fields = [
+                result.wpm.to_string(),
+                result.accuracy.to_string(),
+                result.typed_chars.to_string(),
+                result.correct_chars.to_string(),
+                format!("{:.2}", result.time_spent),
+                result.sentences_completed.to_string(),
+            ];
+            let row: Vec<String> = fields.iter().map(|field| Self::csv_field(field)).collect();
+            csv.push_str(&row.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+
+    // Quote fields containing separators, quotes or line breaks, doubling any inner quotes
+    fn csv_field(value: &str) -> String {
+        if value.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        } else {
+            value.to_string()
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn export_user_data(&self) -> String {
+        serde_json::to_string(&self.user_data).unwrap_or_default()
+    }
+
+    #[wasm_bindgen]
+    pub fn import_user_data(&mut self, json: &str) -> bool {
+        let user_data = match Self::parse_user_data(json) {
+            Ok(user_data) => user_data,
+            Err(_) => {
+                self.log_debug("Import failed: malformed user data");
+                return false;
+            }
+        };
+        
+        if let Some(field) = self.invalid_user_data_field(&user_data) {
+            self.log_debug(&format!("Import failed: invalid {}", field));
+            return false;
+        }
+        
+        self.user_data = user_data;
+        self.save_user_data();
+        true
+    }
+
+    // Imported data must satisfy the same bounds the individual setters enforce
+    fn invalid_user_data_field(&self, user_data: &UserData) -> Option<&'static str> {
+        let valid_duration = |duration: &u32| (MIN_DURATION_SECONDS..=MAX_DURATION_SECONDS).contains(duration);
+        let avatar_chars = user_data.avatar.trim().chars().count();
+        let checks = [
+            // A fresh profile exports with no name yet
+            ("name", user_data.name.is_empty() || user_data.name.len() >= 2),
+            ("language", self.sentences.contains_key(&user_data.language)),
+            ("languages", user_data.languages.iter().all(|code| self.sentences.contains_key(code))),
+            ("duration", valid_duration(&user_data.duration)),
+            ("duration_presets", !user_data.duration_presets.is_empty() && user_data.duration_presets.iter().all(valid_duration)),
+            ("max_wpm", user_data.max_wpm >= 1),
+            ("countdown_seconds", (MIN_COUNTDOWN_SECONDS..=MAX_COUNTDOWN_SECONDS).contains(&user_data.countdown_seconds)),
+            ("chars_per_word", user_data.chars_per_word.is_finite() && user_data.chars_per_word > 0.0),
+            ("sentences_per_round", (1..=MAX_SENTENCES_PER_ROUND).contains(&user_data.sentences_per_round)),
+            ("sentence_target", user_data.sentence_target <= MAX_SENTENCE_TARGET),
+            ("go_hold_ms", user_data.go_hold_ms <= MAX_GO_HOLD_MS),
+            ("completion_threshold", (0.5..=1.0).contains(&user_data.completion_threshold)),
+            ("avatar", (1..=MAX_AVATAR_CHARS).contains(&avatar_chars)),
+        ];
+        checks.iter().find(|(_, valid)| !valid).map(|(field, _)| *field)
+    }
+
+    // Calculation methods
+    fn calculate_wpm(&self, typed: usize, correct: usize, seconds: f64) -> f64 {
+        if typed == 0 {
+            return 0.0;
+        }
+        
+        self.words_per_minute(self.wpm_chars(typed, correct), seconds)
+    }
+
+    fn words_per_minute(&self, chars: usize, seconds: f64) -> f64 {
+        // Words aren't space-delimited in character-based scripts, so speed is reported per character
+        if self.uses_character_metrics() {
+            return self.calculate_cpm(chars, seconds);
+        }
+        
+        if seconds <= 0.0 {
+            return 0.0;
+        }
+        
+        let words = (chars as f64) / self.user_data.chars_per_word;
+        let minutes = seconds / 60.0;
+        (words / minutes).clamp(0.0, self.user_data.max_wpm as f64)
+    }
+
+    // Gross speed scaled by accuracy, rewarding speed and precision together
+    fn calculate_adjusted_wpm(&self) -> f64 {
+        let gross_wpm = self.words_per_minute(self.session_total_typed_chars, self.session_total_time_spent);
+        gross_wpm * self.calculate_session_accuracy() / 100.0
+    }
+
+    fn calculate_cpm(&self, correct: usize, seconds: f64) -> f64 {
+        if seconds <= 0.0 {
+            return 0.0;
+        }
+        
+        let max_cpm = self.user_data.max_wpm as f64 * self.user_data.chars_per_word;
+        let minutes = seconds / 60.0;
+        ((correct as f64) / minutes).clamp(0.0, max_cpm)
+    }
+
+    fn calculate_accuracy(correct: usize, typed: usize) -> f64 {
+        if typed == 0 {
+            return 100.0;
+        }
+        
+        ((correct as f64) / (typed as f64) * 100.0).clamp(0.0, 100.0)
+    }
+
+    fn calculate_session_wpm(&self) -> f64 {
+        self.calculate_wpm(self.session_total_typed_chars, self.session_total_correct_chars, self.session_total_time_spent)
+    }
+
+    fn calculate_session_accuracy(&self) -> f64 {
+        Self::calculate_accuracy(self.session_total_correct_chars, self.session_total_typed_chars)
+    }
+
+    // Net WPM only credits correctly typed characters; gross counts every keystroke
+    fn wpm_chars(&self, typed: usize, correct: usize) -> usize {
+        match self.user_data.wpm_mode {
+            WpmMode::Gross => typed,
+            WpmMode::Net => correct,
+        }
+    }
+
+    fn history_mean(&self, metric: impl Fn(&SessionResult) -> u32) -> f64 {
+        let history = &self.user_data.session_history;
+        if history.is_empty() {
+            return 0.0;
+        }
+        history.iter().map(|result| metric(result) as f64).sum::<f64>() / history.len() as f64
+    }
+
+    // Coefficient of variation of keystroke intervals, inverted so steady rhythm scores high
+    fn calculate_consistency(&self) -> f64 {
+        if self.keystroke_intervals.len() < 2 {
+            return 100.0;
+        }
+        
+        let count = self.keystroke_intervals.len() as f64;
+        let mean = self.keystroke_intervals.iter().sum::<f64>() / count;
+        if mean <= 0.0 {
+            return 100.0;
+        }
+        
+        let variance = self.keystroke_intervals.iter()
+            .map(|interval| (interval - mean).powi(2))
+            .sum::<f64>() / count;
+        let coefficient_of_variation = variance.sqrt() / mean;
+        ((1.0 - coefficient_of_variation) * 100.0).clamp(0.0, 100.0)
+    }
+
+    // Extrapolates the correct-character rate so far over the whole configured duration
+    fn calculate_projected_wpm(&self) -> f64 {
+        let Some(elapsed) = self.session_elapsed() else {
+            return 0.0;
+        };
+        
+        let duration = self.user_data.duration as f64;
+        let elapsed = elapsed.min(duration);
+        if elapsed <= 0.0 || duration <= 0.0 {
+            return 0.0;
+        }
+        
+        let correct = (self.session_total_correct_chars + self.correct_chars) as f64;
+        let projected_chars = (correct / elapsed * duration).round() as usize;
+        self.words_per_minute(projected_chars, duration)
+    }
+
+    // Everyone playing on the same calendar day shares one seed, e.g. 20251015
+    fn seed_for_date(year: u32, month: u32, day: u32) -> u64 {
+        (year as u64) * 10_000 + (month as u64) * 100 + (day as u64)
+    }
+
+    // Seconds of play since the session started, not counting time spent paused
+    fn session_elapsed(&self) -> Option<f64> {
+        let session_start = self.session_start_time?;
+        let now = Date::now();
+        let current_pause = self.pause_started_at.map_or(0.0, |paused_at| now - paused_at);
+        Some(((now - session_start - self.paused_duration - current_pause) / 1000.0).max(0.0))
+    }
+
+    fn is_untimed(&self) -> bool {
+        self.practice_mode || self.zen_mode
+    }
+
+    fn is_time_expired(&self) -> bool {
+        if self.is_untimed() {
+            return false;
+        }
+        
+        if let Some(elapsed) = self.session_elapsed() {
+            elapsed >= (self.user_data.duration as f64)
+        } else {
+            false
+        }
+    }
+
+    fn get_remaining_time(&self) -> f64 {
+        if self.zen_mode {
+            return f64::INFINITY;
+        }
+        
+        if let Some(elapsed) = self.session_elapsed() {
+            ((self.user_data.duration as f64) - elapsed).max(0.0)
+        } else {
+            self.user_data.duration as f64
+        }
+    }
+
+    fn create_progress_result(&self, is_complete: bool, time_expired: bool) -> JsValue {
+        let mut result = HashMap::new();
+        result.insert("correct_chars".to_string(), self.correct_chars as f64);
+        result.insert("typed_chars".to_string(), self.typed_chars as f64);
+        result.insert("wpm".to_string(), self.calculate_current_wpm());
+        result.insert("accuracy".to_string(), self.calculate_current_accuracy());
+        result.insert("is_complete".to_string(), if is_complete { 1.0 } else { 0.0 });
+        result.insert("time_expired".to_string(), if time_expired { 1.0 } else { 0.0 });
+        result.insert("remaining_time".to_string(), self.get_remaining_time());
+        result.insert("blocked_at_index".to_string(), self.blocked_at_index.map_or(-1.0, |index| index as f64));
+        result.insert("first_error_index".to_string(), self.first_error_index.map_or(-1.0, |index| index as f64));
+        result.insert("overtyped_chars".to_string(), self.overtyped_chars as f64);
+        
+        serde_wasm_bindgen::to_value(&result).unwrap_or(JsValue::NULL)
+    }
+
+    fn current_sentence_seconds(&self) -> f64 {
+        match self.start_time {
+            Some(start) => ((Date::now() - start) / 1000.0).max(0.0),
+            None => 0.0,
+        }
+    }
+
+    fn calculate_current_wpm(&self) -> f64 {
+        let seconds = self.current_sentence_seconds();
+        if self.typed_chars == 0 || seconds < 1.0 {
+            return 0.0;
+        }
+        
+        self.calculate_wpm(self.typed_chars, self.correct_chars, seconds)
+    }
+
+    fn calculate_current_cpm(&self) -> f64 {
+        let seconds = self.current_sentence_seconds();
+        if self.typed_chars == 0 || seconds < 1.0 {
+            return 0.0;
+        }
+        
+        self.calculate_cpm(self.correct_chars, seconds)
+    }
+
+    fn calculate_current_accuracy(&self) -> f64 {
+        Self::calculate_accuracy(self.correct_chars, self.typed_chars)
+    }
+
+    // UI helper methods
+    fn show_screen(&self, screen_id: &str) {
+        if let Some(window) = window() {
+            if let Some(document) = window.document() {
+                // Hide all screens
+                let screens = ["welcome-screen", "language-screen", "timer-screen", 
+                             "countdown-screen", "game-screen", "pause-screen", "times-up-screen", "results-screen"];
+                
+                for screen in &screens {
+                    if let Some(element) = document.get_element_by_id(screen) {
+                        element.set_class_name("screen hidden");
+                    }
+                }
+                
+                // Show target screen
+                if let Some(element) = document.get_element_by_id(screen_id) {
+                    element.set_class_name("screen active");
+                }
+                
+                self.update_personalization();
+            }
+        }
+    }
+
+    fn update_personalization(&self) {
+        if let Some(window) = window() {
+            if let Some(document) = window.document() {
+                // Update name displays
+                let name_elements = ["player-name-display", "player-name-timer", 
+                                   "player-name-countdown", "player-name-game", 
+                                   "player-name-times-up", "results-player-name"];
+                
+                for element_id in &name_elements {
+                    if let Some(element) = document.get_element_by_id(element_id) {
+                        element.set_text_content(Some(&self.user_data.name));
+                    }
+                }
+                
+                let avatar_elements = ["player-avatar-display", "player-avatar-game", "results-player-avatar"];
+                
+                for element_id in &avatar_elements {
+                    if let Some(element) = document.get_element_by_id(element_id) {
+                        element.set_text_content(Some(&self.user_data.avatar));
+                    }
+                }
+                
+                // Update language displays
+                if let Some(element) = document.get_element_by_id("selected-language-display") {
+                    element.set_text_content(Some(&self.user_data.language_name));
+                }
+                
+                // Update duration displays
+                if let Some(element) = document.get_element_by_id("selected-duration-display") {
+                    let duration_text = self.format_duration(self.user_data.duration, false);
+                    element.set_text_content(Some(&duration_text));
+                }
+            }
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn is_rtl(lang_code: &str) -> bool {
+        matches!(lang_code, "ar")
+    }
+
+    #[wasm_bindgen]
+    pub fn is_practice_set(lang_code: &str) -> bool {
+        matches!(lang_code, "numbers" | "symbols")
+    }
+
+    #[wasm_bindgen]
+    pub fn is_character_language(lang_code: &str) -> bool {
+        matches!(lang_code, "zh")
+    }
+
+    // A mix that includes a character-based language is scored per character throughout
+    fn uses_character_metrics(&self) -> bool {
+        Self::is_character_language(&self.user_data.language)
+            || self.user_data.languages.iter().any(|code| Self::is_character_language(code))
+    }
+
+    // Compact reads "1m 45s" for the results screen, otherwise "1 minute 45 seconds"
+    fn format_duration(&self, seconds: u32, compact: bool) -> String {
+        let minutes = seconds / 60;
+        let remainder = seconds % 60;
+        let (minutes_text, seconds_text) = if compact {
+            (format!("{}m", minutes), format!("{}s", remainder))
+        } else {
+            (format!("{} minute{}", minutes, if minutes != 1 { "s" } else { "" }), format!("{} seconds", remainder))
+        };
+        match (minutes, remainder) {
+            (0, _) => seconds_text,
+            (_, 0) => minutes_text,
+            _ => format!("{} {}", minutes_text, seconds_text),
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn format_time_spent(&self) -> String {
+        let time_spent = self.session_result.as_ref()
+            .map_or(self.session_total_time_spent, |result| result.time_spent);
+        self.format_duration(time_spent.round() as u32, true)
+    }
+
+    fn display_sentence(&self, sentence: &str) {
+        if let Some(window) = window() {
+            if let Some(document) = window.document() {
+                if let Some(sentence_el) = document.get_element_by_id("sentence") {
+                    sentence_el.set_inner_html("");
+                    let direction = if Self::is_rtl(&self.sentence_language) { "rtl" } else { "ltr" };
+                    sentence_el.set_attribute("dir", direction).ok();
+                    
+                    for (i, unit) in graphemes(sentence).into_iter().enumerate() {
+                        if let Ok(span) = document.create_element("span") {
+                            span.set_text_content(Some(unit));
+                            span.set_class_name("char");
+                            span.set_attribute("data-index", &i.to_string()).ok();
+                            sentence_el.append_child(&span).ok();
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn display_celebration(&self) {
+        if let Some(window) = window() {
+            if let Some(document) = window.document() {
+                if let Some(sentence_el) = document.get_element_by_id("sentence") {
+                    sentence_el.set_text_content(Some("Great job! 🌟"));
+                }
+            }
+        }
+    }
+
+    fn update_countdown_display(&self) {
+        if let Some(window) = window() {
+            if let Some(document) = window.document() {
+                if let Some(element) = document.get_element_by_id("countdown-number") {
+                    let text = if self.countdown_value == 0 { "GO!".to_string() } else { self.countdown_value.to_string() };
+                    element.set_text_content(Some(&text));
+                }
+            }
+        }
+    }
+
+    fn countdown_message(&self) -> String {
+        let messages = self.countdown_messages.get(&self.user_data.language)
+            .or_else(|| self.countdown_messages.get("en"));
+        
+        match messages {
+            Some(messages) if !messages.is_empty() => {
+                // Longer countdowns reuse the opening message for their extra seconds
+                let message_index = (self.countdown_value as usize).min(messages.len() - 1);
+                messages[message_index].clone()
+            }
+            _ => String::new(),
+        }
+    }
+
+    fn update_countdown_message(&self) {
+        if let Some(window) = window() {
+            if let Some(document) = window.document() {
+                if let Some(element) = document.get_element_by_id("countdown-text") {
+                    element.set_text_content(Some(&self.countdown_message()));
+                }
+            }
+        }
+    }
+
+    fn display_results(&self) {
+        self.log_debug("display_results called");
+        if let Some(result) = &self.session_result {
+            self.log_debug(&format!("Session result found: WPM={}, Accuracy={}%, Chars={}, Sentences={}", 
+                        result.wpm, result.accuracy, result.typed_chars, result.sentences_completed));
+            
+            if let Some(window) = window() {
+                if let Some(document) = window.document() {
+                    // Update final stats
+                    if let Some(element) = document.get_element_by_id("final-wpm") {
+                        element.set_text_content(Some(&result.wpm.to_string()));
+                        self.log_debug(&format!("Updated final-wpm: {}", result.wpm));
+                    }
+                    if let Some(element) = document.get_element_by_id("final-adjusted-wpm") {
+                        element.set_text_content(Some(&result.adjusted_wpm.to_string()));
+                    }
+                    if let Some(element) = document.get_element_by_id("final-accuracy") {
+                        element.set_text_content(Some(&format!("{}%", result.accuracy)));
+                        self.log_debug(&format!("Updated final-accuracy: {}%", result.accuracy));
+                    }
+                    if let Some(element) = document.get_element_by_id("total-characters") {
+                        element.set_text_content(Some(&result.typed_chars.to_string()));
+                        self.log_debug(&format!("Updated total-characters: {}", result.typed_chars));
+                    }
+                    if let Some(element) = document.get_element_by_id("words-typed") {
+                        element.set_text_content(Some(&result.words_typed.to_string()));
+                    }
+                    if let Some(element) = document.get_element_by_id("sentences-completed") {
+                        element.set_text_content(Some(&result.sentences_completed.to_string()));
+                        self.log_debug(&format!("Updated sentences-completed: {}", result.sentences_completed));
+                    }
+                    if let Some(element) = document.get_element_by_id("results-screen") {
+                        let is_record = result.new_wpm_record || result.new_accuracy_record;
+                        element.class_list().toggle_with_force("new-record", is_record).ok();
+                        element.class_list().toggle_with_force("goal-met", result.goal_met).ok();
+                        element.class_list().toggle_with_force("cpm", result.is_cpm).ok();
+                    }
+                } else {
+                    self.log_debug("Document not found");
+                }
+            } else {
+                self.log_debug("Window not found");
+            }
+        } else {
+            self.log_debug("No session result found to display");
+        }
+    }
+
+    fn record_leaderboard_entry(&mut self, entry: LeaderboardEntry) {
+        self.leaderboard.push(entry);
+        self.leaderboard.sort_by(|a, b| b.wpm.cmp(&a.wpm).then(b.accuracy.cmp(&a.accuracy)));
+        self.leaderboard.truncate(LEADERBOARD_SIZE);
+        self.save_leaderboard();
+    }
+
+    // Data persistence
+    // Private browsing can deny storage entirely or reject writes; progress then lives in memory only
+    fn save_user_data(&mut self) {
+        let mut saved = false;
+        if let Some(window) = window() {
+            if let Some(storage) = window.local_storage().ok().flatten() {
+                if let Ok(serialized) = serde_json::to_string(&self.user_data) {
+                    saved = storage.set_item(&self.user_data_key(), &serialized).is_ok();
+                }
+            }
+        }
+        
+        if !saved && self.storage_available {
+            self.log_debug("localStorage unavailable, progress will not persist");
+        }
+        self.storage_available = saved;
+    }
+
+    fn clear_stored_user_data(&self) {
+        if let Some(window) = window() {
+            if let Some(storage) = window.local_storage().ok().flatten() {
+                storage.remove_item(&self.user_data_key()).ok();
+            }
+        }
+    }
+
+    // Every key the app writes shares the prefix, whichever profile or feature wrote it
+    fn clear_all_stored_data(&self) {
+        if let Some(window) = window() {
+            if let Some(storage) = window.local_storage().ok().flatten() {
+                let keys: Vec<String> = (0..storage.length().unwrap_or(0))
+                    .filter_map(|index| storage.key(index).ok().flatten())
+                    .filter(|key| key.starts_with("typingApp"))
+                    .collect();
+                for key in keys {
+                    storage.remove_item(&key).ok();
+                }
+            }
+        }
+    }
+
+    fn load_user_data(&mut self) {
+        self.storage_available = false;
+        if let Some(window) = window() {
+            if let Some(storage) = window.local_storage().ok().flatten() {
+                self.storage_available = true;
+                if let Ok(Some(data)) = storage.get_item(&self.user_data_key()) {
+                    if let Ok(user_data) = Self::parse_user_data(&data) {
+                        self.user_data = user_data;
+                    }
+                }
+            }
+        }
+    }
+
+    // Saves from before duration_chosen existed count a non-default duration as the player's pick
+    fn parse_user_data(json: &str) -> serde_json::Result<UserData> {
+        let value: serde_json::Value = serde_json::from_str(json)?;
+        let legacy = value.get("duration_chosen").is_none();
+        let mut user_data: UserData = serde_json::from_value(value)?;
+        if legacy && user_data.duration != UserData::default().duration {
+            user_data.duration_chosen = true;
+        }
+        Ok(user_data)
+    }
+
+    // Each profile gets its own key; the unnamed default keeps the original key
+    fn user_data_key(&self) -> String {
+        if self.active_profile.is_empty() {
+            "typingAppUserData".to_string()
+        } else {
+            format!("typingAppUserData:{}", self.active_profile)
+        }
+    }
+
+    // Sessions are per player so one child's unfinished round never resumes under another's name
+    fn active_session_key(&self) -> String {
+        if self.active_profile.is_empty() {
+            "typingAppActiveSession".to_string()
+        } else {
+            format!("typingAppActiveSession:{}", self.active_profile)
+        }
+    }
+
+    fn save_profiles(&self) {
+        if let Some(window) = window() {
+            if let Some(storage) = window.local_storage().ok().flatten() {
+                if let Ok(serialized) = serde_json::to_string(&self.profiles) {
+                    storage.set_item("typingAppProfiles", &serialized).ok();
+                }
+                storage.set_item("typingAppActiveProfile", &self.active_profile).ok();
+            }
+        }
+    }
+
+    fn load_profiles(&mut self) {
+        if let Some(window) = window() {
+            if let Some(storage) = window.local_storage().ok().flatten() {
+                if let Ok(Some(data)) = storage.get_item("typingAppProfiles") {
+                    if let Ok(profiles) = serde_json::from_str::<Vec<String>>(&data) {
+                        self.profiles = profiles;
+                    }
+                }
+                if let Ok(Some(active)) = storage.get_item("typingAppActiveProfile") {
+                    if self.profiles.contains(&active) {
+                        self.active_profile = active;
+                    }
+                }
+            }
+        }
+    }
+
+    fn save_active_session(&self) {
+        let Some(session_start_time) = self.session_start_time else {
+            return;
+        };
+        // Saved right after a sentence completes, so resume on the one queued up next
+        let (sentence_language, sentence) = match &self.next_sentence {
+            Some(next) => next.clone(),
+            None => (self.sentence_language.clone(), self.original_sentence.clone()),
+        };
+        let session = ActiveSession {
+            session_start_time,
+            paused_duration: self.paused_duration,
+            typed_chars: self.session_total_typed_chars,
+            correct_chars: self.session_total_correct_chars,
+            time_spent: self.session_total_time_spent,
+            sentences_completed: self.session_sentences_completed,
+            sentences_skipped: self.session_sentences_skipped,
+            words_typed: self.session_words_typed,
+            current_streak: self.current_streak,
+            daily_challenge: self.daily_challenge,
+            sentence_language,
+            sentence,
+        };
+        
+        if let Some(window) = window() {
+            if let Some(storage) = window.local_storage().ok().flatten() {
+                if let Ok(serialized) = serde_json::to_string(&session) {
+                    storage.set_item(&self.active_session_key(), &serialized).ok();
+                }
+            }
+        }
+    }
+
+    fn clear_active_session(&self) {
+        if let Some(window) = window() {
+            if let Some(storage) = window.local_storage().ok().flatten() {
+                storage.remove_item(&self.active_session_key()).ok();
+            }
+        }
+    }
+
+    fn load_active_session(&self) -> Option<ActiveSession> {
+        let storage = window()?.local_storage().ok().flatten()?;
+        let data = storage.get_item(&self.active_session_key()).ok().flatten()?;
+        serde_json::from_str::<ActiveSession>(&data).ok()
+    }
+
+    #[wasm_bindgen]
+    pub fn resume_active_session(&mut self) -> bool {
+        let Some(session) = self.load_active_session() else {
+            return false;
+        };
+        
+        // Sessions whose clock would already have run out are not worth restoring
+        let elapsed_ms = Date::now() - session.session_start_time - session.paused_duration;
+        if elapsed_ms >= self.user_data.duration as f64 * 1000.0 || session.sentence.is_empty() {
+            self.clear_active_session();
+            return false;
+        }
+        
+        self.initialize_game();
+        self.practice_mode = false;
+        self.zen_mode = false;
+        self.session_start_time = Some(session.session_start_time);
+        self.paused_duration = session.paused_duration;
+        self.session_total_typed_chars = session.typed_chars;
+        self.session_total_correct_chars = session.correct_chars;
+        self.session_total_time_spent = session.time_spent;
+        self.session_sentences_completed = session.sentences_completed;
+        self.session_sentences_skipped = session.sentences_skipped;
+        self.session_words_typed = session.words_typed;
+        self.current_streak = session.current_streak;
+        self.daily_challenge = session.daily_challenge;
+        
+        self.sentence_language = session.sentence_language.clone();
+        self.original_sentence = session.sentence.clone();
+        self.current_sentence = if self.user_data.simplify_text {
+            Self::simplify_sentence(&session.sentence)
+        } else {
+            session.sentence.clone()
+        };
+        self.played_sentences.push((session.sentence_language, session.sentence));
+        
+        self.show_screen("game-screen");
+        self.set_state(AppState::Playing);
+        self.display_sentence(&self.current_sentence.clone());
+        self.focus_typing_input();
+        start_game_timer();
+        // Keep the snapshot until the next sentence completes in case of another reload
+        self.save_active_session();
+        self.log_debug("Resumed interrupted session");
+        true
+    }
+
+    fn save_leaderboard(&self) {
+        if let Some(window) = window() {
+            if let Some(storage) = window.local_storage().ok().flatten() {
+                if let Ok(serialized) = serde_json::to_string(&self.leaderboard) {
+                    storage.set_item("typingAppLeaderboard", &serialized).ok();
+                }
+            }
+        }
+    }
+
+    fn load_leaderboard(&mut self) {
+        if let Some(window) = window() {
+            if let Some(storage) = window.local_storage().ok().flatten() {
+                if let Ok(Some(data)) = storage.get_item("typingAppLeaderboard") {
+                    if let Ok(leaderboard) = serde_json::from_str::<Vec<LeaderboardEntry>>(&data) {
+                        self.leaderboard = leaderboard;
+                    }
+                }
+            }
+        }
+    }
+
+    // Getters for UI
+    #[wasm_bindgen(getter)]
+    pub fn case_insensitive(&self) -> bool {
+        self.user_data.case_insensitive
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn user_name(&self) -> String {
+        self.user_data.name.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn avatar(&self) -> String {
+        self.user_data.avatar.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn current_language(&self) -> String {
+        self.user_data.language.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn current_sentence(&self) -> String {
+        self.current_sentence.clone()
+    }
+
+    // Half the score comes from overall length, half from average word length
+    #[wasm_bindgen(getter)]
+    pub fn sentence_difficulty_score(&self) -> f64 {
+        let words: Vec<&str> = self.current_sentence.split_whitespace().collect();
+        if words.is_empty() {
+            return 0.0;
+        }
+        
+        let total_chars = self.current_sentence.chars().count() as f64;
+        let word_chars: usize = words.iter().map(|word| word.chars().count()).sum();
+        let avg_word_len = word_chars as f64 / words.len() as f64;
+        
+        let length_score = (total_chars / HARDEST_SENTENCE_CHARS).min(1.0);
+        let word_score = ((avg_word_len - 1.0) / (HARDEST_AVG_WORD_CHARS - 1.0)).clamp(0.0, 1.0);
+        (length_score + word_score) * 50.0
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn next_sentence(&self) -> Option<String> {
+        self.next_sentence.as_ref().map(|(_, sentence)| {
+            if self.user_data.simplify_text {
+                Self::simplify_sentence(sentence)
+            } else {
+                sentence.clone()
+            }
+        })
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn session_wpm(&self) -> u32 {
+        self.calculate_session_wpm() as u32
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn adjusted_wpm(&self) -> f64 {
+        self.calculate_adjusted_wpm()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn session_typed_chars(&self) -> u32 {
+        // A finished sentence waiting on its celebration is already in the session total
+        let in_progress = if self.awaiting_advance { 0 } else { self.typed_chars };
+        (self.session_total_typed_chars + in_progress) as u32
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn session_cpm(&self) -> f64 {
+        self.calculate_cpm(self.session_total_correct_chars, self.session_total_time_spent)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn current_cpm(&self) -> f64 {
+        self.calculate_current_cpm()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn session_accuracy(&self) -> u32 {
+        self.calculate_session_accuracy() as u32
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn daily_seed(&self) -> u64 {
+        let today = Date::new_0();
+        Self::seed_for_date(today.get_full_year(), today.get_month() + 1, today.get_date())
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn is_daily_challenge(&self) -> bool {
+        self.daily_challenge
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn row_mistakes(&self) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.row_mistakes).unwrap_or(JsValue::NULL)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn mistake_counts(&self) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.user_data.mistake_counts).unwrap_or(JsValue::NULL)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn accuracy_trend(&self) -> JsValue {
+        let trend: Vec<u32> = self.sentence_results.iter().map(|result| result.accuracy).collect();
+        serde_wasm_bindgen::to_value(&trend).unwrap_or(JsValue::NULL)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn wpm_series(&self) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.wpm_samples).unwrap_or(JsValue::NULL)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn leaderboard(&self) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.leaderboard).unwrap_or(JsValue::NULL)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn completion_count(&self) -> u32 {
+        self.user_data.completed_sentences.len() as u32
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn total_sentence_count(&self) -> u32 {
+        self.sentences.values().map(|pool| pool.len()).sum::<usize>() as u32
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn average_wpm(&self) -> f64 {
+        self.history_mean(|result| result.wpm)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn average_accuracy(&self) -> f64 {
+        self.history_mean(|result| result.accuracy)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn player_summary(&self) -> JsValue {
+        let summary = PlayerSummary {
+            name: self.user_data.name.clone(),
+            best_wpm: self.user_data.best_wpm,
+            best_accuracy: self.user_data.best_accuracy,
+            total_sessions: self.user_data.total_sessions,
+            best_streak: self.user_data.best_streak,
+        };
+        serde_wasm_bindgen::to_value(&summary).unwrap_or(JsValue::NULL)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn duration_presets(&self) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.user_data.duration_presets).unwrap_or(JsValue::NULL)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn session_result_json(&self) -> String {
+        serde_json::to_string(&self.session_result).unwrap_or_else(|_| "null".to_string())
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn time_per_language(&self) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.user_data.time_per_language).unwrap_or(JsValue::NULL)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn current_streak(&self) -> u32 {
+        self.current_streak
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn best_streak(&self) -> u32 {
+        self.user_data.best_streak
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn backspace_count(&self) -> u32 {
+        self.backspace_count
+    }
+
+    // Share of keystrokes that ended up as correct characters; 100 means nothing was wasted
+    #[wasm_bindgen(getter)]
+    pub fn typing_efficiency(&self) -> f64 {
+        if self.total_keystrokes == 0 {
+            return 100.0;
+        }
+        
+        let in_progress = if self.awaiting_advance { 0 } else { self.correct_chars };
+        let correct = self.session_total_correct_chars + in_progress;
+        (correct as f64 / self.total_keystrokes as f64 * 100.0).clamp(0.0, 100.0)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn remaining_sentences(&self) -> i32 {
+        // -1 means no count applies: no target is set, or the mode ignores it
+        if self.user_data.sentence_target == 0 || self.is_untimed() {
+            return -1;
+        }
+        (self.user_data.sentence_target as i32 - self.session_sentences_completed as i32).max(0)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn sentences_skipped(&self) -> u32 {
+        self.session_sentences_skipped as u32
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn consistency_score(&self) -> f64 {
+        self.calculate_consistency()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn auto_advance_delay_ms(&self) -> u32 {
+        self.user_data.auto_advance_delay_ms
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn kiosk_restart_pending(&self) -> bool {
+        self.kiosk_timeout_id.is_some()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn awaiting_next_sentence(&self) -> bool {
+        self.awaiting_next_sentence
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn awaiting_advance(&self) -> bool {
+        self.awaiting_advance
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn is_session_active(&self) -> bool {
+        self.is_active
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn is_playing(&self) -> bool {
+        self.app_state == AppState::Playing
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn is_practice(&self) -> bool {
+        self.practice_mode
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn has_previous_session(&self) -> bool {
+        self.session_result.as_ref().is_some_and(|r| r.has_previous_session)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn wpm_delta(&self) -> i32 {
+        self.session_result.as_ref().and_then(|r| r.wpm_delta).unwrap_or(0)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn accuracy_delta(&self) -> i32 {
+        self.session_result.as_ref().and_then(|r| r.accuracy_delta).unwrap_or(0)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn goal_met(&self) -> bool {
+        self.session_result.as_ref().is_some_and(|r| r.goal_met)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn ended_incomplete(&self) -> bool {
+        self.session_result.as_ref().is_some_and(|r| r.ended_incomplete)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn new_wpm_record(&self) -> bool {
+        self.session_result.as_ref().is_some_and(|r| r.new_wpm_record)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn new_accuracy_record(&self) -> bool {
+        self.session_result.as_ref().is_some_and(|r| r.new_accuracy_record)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn current_sentence_elapsed(&self) -> f64 {
+        self.current_sentence_seconds()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn countdown_value(&self) -> u32 {
+        self.countdown_value
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn projected_wpm(&self) -> f64 {
+        self.calculate_projected_wpm()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn storage_available(&self) -> bool {
+        self.storage_available
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn remaining_time(&self) -> f64 {
+        self.get_remaining_time()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn time_progress(&self) -> f64 {
+        match self.session_elapsed() {
+            Some(elapsed) if self.user_data.duration > 0 && !self.zen_mode => {
+                (elapsed / self.user_data.duration as f64).clamp(0.0, 1.0)
+            }
+            _ => 0.0,
+        }
+    }
+    
+    // Debug function to test Rust-JS connection
+    #[wasm_bindgen]
+    pub fn test_connection(&self) -> String {
+        self.log_debug("Rust function called successfully!");
+        format!("Rust is working! Current sentence: {}", self.current_sentence)
+    }
+}
+
+impl Default for TypingApp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for TypingApp {
+    // A pending timer must not fire into a closure freed along with the app
+    fn drop(&mut self) {
+        self.cancel_pending_advance();
+        self.cancel_kiosk_restart();
+        if let Some(timeout_id) = self.go_timeout_id.take() {
+            clearTimeout(timeout_id);
+        }
+    }
+}
+
+#[cfg_attr(not(test), wasm_bindgen(start))]
+pub fn main() {
+    console_log!("Rust TypingApp initialized!");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app() -> TypingApp {
+        let mut app = TypingApp::new();
+        app.logging_enabled = false;
+        app
+    }
+
+    // 100 keystrokes, 80 of them correct, over one minute
+    fn eighty_percent_session(app: &mut TypingApp) {
+        app.session_total_typed_chars = 100;
+        app.session_total_correct_chars = 80;
+        app.session_total_time_spent = 60.0;
+    }
+
+    #[test]
+    fn net_wpm_counts_only_correct_chars() {
+        let mut app = app();
+        eighty_percent_session(&mut app);
+        assert_eq!(app.user_data.wpm_mode, WpmMode::Net);
+        assert_eq!(app.calculate_session_wpm(), 16.0);
+    }
+
+    #[test]
+    fn gross_wpm_counts_every_typed_char() {
+        let mut app = app();
+        eighty_percent_session(&mut app);
+        app.user_data.wpm_mode = WpmMode::Gross;
+        assert_eq!(app.calculate_session_wpm(), 20.0);
+    }
+
+    #[test]
+    fn wpm_chars_follows_mode() {
+        let mut app = app();
+        assert_eq!(app.wpm_chars(100, 80), 80);
+        app.user_data.wpm_mode = WpmMode::Gross;
+        assert_eq!(app.wpm_chars(100, 80), 100);
+    }
+
+    #[test]
+    fn wpm_is_clamped_to_max() {
+        let app = app();
+        assert_eq!(app.calculate_wpm(10_000, 10_000, 60.0), 300.0);
+        assert_eq!(app.calculate_wpm(0, 0, 60.0), 0.0);
+    }
+
+    #[test]
+    fn accuracy_is_a_percentage_of_typed_chars() {
+        assert_eq!(TypingApp::calculate_accuracy(80, 100), 80.0);
+        assert_eq!(TypingApp::calculate_accuracy(0, 0), 100.0);
+    }
+
+    #[test]
+    fn every_countdown_value_has_a_message() {
+        let mut app = app();
+        for language in ["en", "es", "fr", "id", "merdeka", "zh"] {
+            app.user_data.language = language.to_string();
+            for value in 0..=MAX_COUNTDOWN_SECONDS {
+                app.countdown_value = value;
+                assert!(!app.countdown_message().is_empty(), "{} at {}", language, value);
+            }
+        }
+    }
+
+    #[test]
+    fn sentence_progress_tracks_typed_share() {
+        let mut app = app();
+        app.current_sentence = "El gatito mágico".to_string();
+        assert_eq!(app.sentence_progress(""), 0.0);
+        assert!((app.sentence_progress("El gatit") - 0.5).abs() < 1e-9);
+        assert_eq!(app.sentence_progress("El gatito mágico"), 1.0);
+        assert_eq!(app.sentence_progress("El gatito mágico!!"), 1.0);
+    }
+
+    #[test]
+    fn even_rhythm_scores_higher_than_erratic() {
+        let mut app = app();
+        app.keystroke_intervals = vec![200.0; 10];
+        let even = app.calculate_consistency();
+        app.keystroke_intervals = vec![50.0, 600.0, 120.0, 900.0, 80.0, 400.0, 60.0, 700.0];
+        let erratic = app.calculate_consistency();
+        assert_eq!(even, 100.0);
+        assert!(even > erratic);
+    }
+
+    #[test]
+    fn only_arabic_is_right_to_left() {
+        assert!(TypingApp::is_rtl("ar"));
+        assert!(!TypingApp::is_rtl("en"));
+        assert!(!TypingApp::is_rtl("zh"));
+    }
+
+    #[test]
+    fn two_sentence_pool_alternates() {
+        let mut app = app();
+        app.user_data.language = "en".to_string();
+        app.sentences.insert("en".to_string(), tagged(&[("test", "First."), ("test", "Second.")]));
         
-        // Calculate final results
-        let wpm = self.calculate_session_wpm();
-        let accuracy = self.calculate_session_accuracy();
+        let mut previous = app.pick_sentence("").1;
+        for _ in 0..20 {
+            let next = app.pick_sentence(&previous).1;
+            assert_ne!(next, previous);
+            previous = next;
+        }
+    }
+
+    #[test]
+    fn same_seed_gives_same_sentence_sequence() {
+        let draw = |seed| {
+            let mut app = app();
+            app.set_seed(seed);
+            let mut previous = String::new();
+            (0..10).map(|_| {
+                previous = app.pick_round(&previous).1;
+                previous.clone()
+            }).collect::<Vec<_>>()
+        };
+        assert_eq!(draw(42), draw(42));
+        assert_ne!(draw(42), draw(7));
+    }
+
+    #[test]
+    fn seed_for_date_depends_only_on_the_date() {
+        assert_eq!(TypingApp::seed_for_date(2025, 10, 15), TypingApp::seed_for_date(2025, 10, 15));
+        assert_eq!(TypingApp::seed_for_date(2025, 10, 15), 20251015);
+        assert_ne!(TypingApp::seed_for_date(2025, 10, 15), TypingApp::seed_for_date(2025, 10, 16));
+    }
+
+    #[test]
+    fn remaining_text_starts_at_the_first_mismatch() {
+        let mut app = app();
+        app.current_sentence = "The cat naps.".to_string();
+        assert_eq!(app.remaining_text(""), "The cat naps.");
+        assert_eq!(app.remaining_text("The c"), "at naps.");
+        assert_eq!(app.remaining_text("Tha cat"), "e cat naps.");
+    }
+
+    #[test]
+    fn spanish_countdown_uses_spanish_messages() {
+        let mut app = app();
+        app.user_data.language = "es".to_string();
+        app.countdown_value = 0;
+        assert_eq!(app.countdown_message(), "¡YA! ¡Ayuda a nuestro amigo mágico! 🌟");
+        app.countdown_value = 3;
+        assert_eq!(app.countdown_message(), "¡Pon las manos en el teclado como un pianista! 🎹");
+    }
+
+    #[test]
+    fn chars_per_word_sets_the_wpm_divisor() {
+        let mut app = app();
+        assert_eq!(app.words_per_minute(120, 60.0), 24.0);
+        app.user_data.chars_per_word = 6.0;
+        assert_eq!(app.words_per_minute(120, 60.0), 20.0);
+    }
+
+    #[test]
+    fn simplify_sentence_drops_case_and_punctuation() {
+        assert_eq!(TypingApp::simplify_sentence("The Cat, the Dog!"), "the cat the dog");
+        assert_eq!(TypingApp::simplify_sentence("¡Hola, señor Pérez!"), "hola señor pérez");
+        assert_eq!(TypingApp::simplify_sentence("Wait - what?"), "wait what");
+    }
+
+    #[test]
+    fn keys_are_filed_under_their_keyboard_row() {
+        let row = |c| TypingApp::keyboard_row(KeyboardLayout::Qwerty, c);
+        assert_eq!(row('q'), "top");
+        assert_eq!(row('A'), "home");
+        assert_eq!(row('m'), "bottom");
+        assert_eq!(row('7'), "number");
+        assert_eq!(row(' '), "space");
+        assert_eq!(row('é'), "other");
+    }
+
+    #[test]
+    fn same_mistake_lands_on_the_layouts_own_row() {
+        // 'q' sits on a different row in each layout
+        assert_eq!(TypingApp::keyboard_row(KeyboardLayout::Qwerty, 'q'), "top");
+        assert_eq!(TypingApp::keyboard_row(KeyboardLayout::Azerty, 'q'), "home");
+        assert_eq!(TypingApp::keyboard_row(KeyboardLayout::Dvorak, 'q'), "bottom");
         
-        self.session_result = Some(SessionResult {
-            wpm: wpm as u32,
-            accuracy: accuracy as u32,
-            typed_chars: self.session_total_typed_chars as u32,
-            correct_chars: self.session_total_correct_chars as u32,
-            time_spent: self.session_total_time_spent,
-            sentences_completed: self.session_sentences_completed as u32,
-        });
+        let mut app = app();
+        for layout in [KeyboardLayout::Qwerty, KeyboardLayout::Azerty, KeyboardLayout::Dvorak] {
+            app.user_data.keyboard_layout = layout;
+            app.record_row_mistake('q');
+        }
+        assert_eq!(app.row_mistakes.get("top"), Some(&1));
+        assert_eq!(app.row_mistakes.get("home"), Some(&1));
+        assert_eq!(app.row_mistakes.get("bottom"), Some(&1));
+    }
+
+    #[test]
+    fn cpm_is_wpm_times_chars_per_word() {
+        let mut app = app();
+        eighty_percent_session(&mut app);
+        let wpm = app.calculate_session_wpm();
+        assert!((app.session_cpm() - wpm * app.user_data.chars_per_word).abs() < 1e-9);
+    }
+
+    #[test]
+    fn category_filter_draws_only_animal_sentences() {
+        let mut app = app();
+        app.user_data.language = "en".to_string();
+        app.user_data.category = "animals".to_string();
+        let animals: Vec<&str> = app.sentences["en"].iter()
+            .filter(|entry| entry.category == "animals")
+            .map(|entry| entry.text)
+            .collect();
+        
+        let mut previous = String::new();
+        for _ in 0..30 {
+            previous = app.pick_sentence(&previous).1;
+            assert!(animals.contains(&previous.as_str()), "{}", previous);
+        }
+    }
+
+    #[test]
+    fn valid_prefix_rejects_typos_and_overflow() {
+        let mut app = app();
+        app.current_sentence = "Hi there.".to_string();
+        assert!(app.is_valid_prefix(""));
+        assert!(app.is_valid_prefix("Hi th"));
+        assert!(app.is_valid_prefix("Hi there."));
+        assert!(!app.is_valid_prefix("Hi tx"));
+        assert!(!app.is_valid_prefix("Hi there.."));
+    }
+
+    #[test]
+    fn two_sentences_per_round_roughly_doubles_the_length() {
+        let average_length = |per_round| {
+            let mut app = app();
+            app.set_seed(3);
+            app.user_data.sentences_per_round = per_round;
+            let mut previous = String::new();
+            let total: usize = (0..50).map(|_| {
+                previous = app.pick_round(&previous).1;
+                previous.chars().count()
+            }).sum();
+            total as f64 / 50.0
+        };
+        let ratio = average_length(2) / average_length(1);
+        assert!((1.7..2.3).contains(&ratio), "ratio {}", ratio);
+    }
+
+    #[test]
+    fn adjusted_wpm_scales_gross_speed_by_accuracy() {
+        let mut app = app();
+        app.session_total_typed_chars = 100;
+        app.session_total_correct_chars = 90;
+        app.session_total_time_spent = 60.0;
+        assert!((app.adjusted_wpm() - 20.0 * 0.9).abs() < 1e-9);
+    }
+
+    #[test]
+    fn letters_only_draws_lowercase_sentences() {
+        let mut app = app();
+        app.user_data.letters_only = true;
+        for language in ["en", "es", "fr", "id"] {
+            app.user_data.language = language.to_string();
+            let mut previous = String::new();
+            for _ in 0..10 {
+                previous = app.pick_sentence(&previous).1;
+                assert!(!previous.chars().any(char::is_uppercase), "{}", previous);
+            }
+        }
+        
+        // Pools without lowercase sentences fall back rather than running dry
+        app.user_data.language = "merdeka".to_string();
+        assert!(app.pick_sentence("").1.chars().any(char::is_uppercase));
+    }
+
+    #[test]
+    fn warm_up_sentences_stay_out_of_default_draws() {
+        let mut app = app();
+        assert_eq!(app.sentences["en"].len(), 15);
+        let mut previous = String::new();
+        for _ in 0..50 {
+            previous = app.pick_sentence(&previous).1;
+            assert!(!warm_up_sentences("en").contains(&previous.as_str()), "{}", previous);
+        }
+    }
+
+    #[test]
+    fn session_result_json_has_every_field() {
+        let mut app = app();
+        assert_eq!(app.session_result_json(), "null");
+        
+        app.session_result = Some(SessionResult { wpm: 25, accuracy: 96, ..SessionResult::default() });
+        let json: serde_json::Value = serde_json::from_str(&app.session_result_json()).unwrap();
+        for field in [
+            "wpm", "accuracy", "typed_chars", "correct_chars", "time_spent", "sentences_completed",
+            "new_wpm_record", "new_accuracy_record", "daily_challenge", "goal_met", "is_cpm",
+            "has_previous_session", "wpm_delta", "accuracy_delta", "adjusted_wpm", "ended_incomplete",
+            "words_typed",
+        ] {
+            assert!(json.get(field).is_some(), "missing {}", field);
+        }
+        assert_eq!(json["wpm"], 25);
+        assert_eq!(json["accuracy"], 96);
+    }
+
+    #[test]
+    fn adaptive_selection_favours_sentences_with_missed_characters() {
+        let mut app = app();
+        app.set_seed(11);
+        app.user_data.language = "en".to_string();
+        app.user_data.adaptive_selection = true;
+        app.sentences.insert("en".to_string(), tagged(&[("test", "zz"), ("test", "ab"), ("test", "cd")]));
+        app.user_data.mistake_counts.insert('z', 9);
+        
+        // "zz" weighs 1 + 9 against 1 for each of the others, so about 10 draws in 12
+        let draws = 1200;
+        let hits = (0..draws).filter(|_| app.pick_sentence("").1 == "zz").count();
+        let share = hits as f64 / draws as f64;
+        assert!((0.78..0.88).contains(&share), "share {}", share);
+    }
+
+    #[test]
+    fn unknown_language_falls_back_instead_of_panicking() {
+        let mut app = app();
+        app.user_data.language = "xx".to_string();
+        let (language, sentence) = app.pick_sentence("");
+        assert_eq!(language, "en");
+        assert!(app.sentences["en"].iter().any(|entry| entry.text == sentence));
+    }
+
+    #[test]
+    fn blacklisted_sentence_is_never_selected() {
+        let mut app = app();
+        app.user_data.language = "en".to_string();
+        let banned = app.sentences["en"][0].text.to_string();
+        app.user_data.blacklist.insert(banned.clone());
+        
+        let mut previous = String::new();
+        for _ in 0..60 {
+            previous = app.pick_sentence(&previous).1;
+            assert_ne!(previous, banned);
+        }
+    }
+
+    #[test]
+    fn time_spent_reads_in_minutes_and_seconds() {
+        let mut app = app();
+        app.session_total_time_spent = 45.0;
+        assert_eq!(app.format_time_spent(), "45s");
+        app.session_total_time_spent = 105.0;
+        assert_eq!(app.format_time_spent(), "1m 45s");
+        app.session_total_time_spent = 0.0;
+        assert_eq!(app.format_time_spent(), "0s");
+        assert_eq!(app.format_duration(105, false), "1 minute 45 seconds");
+        assert_eq!(app.format_duration(120, false), "2 minutes");
+    }
+
+    #[test]
+    fn current_accuracy_counts_matching_characters() {
+        let mut app = app();
+        app.current_sentence = "abcd".to_string();
+        assert_eq!(app.current_accuracy("abxy"), 50.0);
+        assert_eq!(app.current_accuracy(""), 100.0);
+    }
+
+    #[test]
+    fn long_sentences_score_as_harder_than_short_ones() {
+        let mut app = app();
+        app.current_sentence = "I am a cat.".to_string();
+        let short = app.sentence_difficulty_score();
+        app.current_sentence = "Extraordinary hippopotamuses wandered through magnificent, unexplored rainforests yesterday afternoon.".to_string();
+        let long = app.sentence_difficulty_score();
+        assert!(short < long, "{} vs {}", short, long);
+        assert!((0.0..=100.0).contains(&short) && (0.0..=100.0).contains(&long));
+        app.current_sentence.clear();
+        assert_eq!(app.sentence_difficulty_score(), 0.0);
+    }
+
+    #[test]
+    fn history_csv_has_a_header_and_a_row_per_session() {
+        let mut app = app();
+        app.user_data.session_history = vec![
+            SessionResult { wpm: 20, accuracy: 90, typed_chars: 110, correct_chars: 99, time_spent: 60.0, sentences_completed: 2, ..SessionResult::default() },
+            SessionResult { wpm: 25, accuracy: 100, typed_chars: 125, correct_chars: 125, time_spent: 59.5, sentences_completed: 3, ..SessionResult::default() },
+        ];
+        let csv = app.export_history_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines, [
+            "wpm,accuracy,typed_chars,correct_chars,time_spent,sentences_completed",
+            "20,90,110,99,60.00,2",
+            "25,100,125,125,59.50,3",
+        ]);
+        assert_eq!(TypingApp::csv_field("a,\"b\""), "\"a,\"\"b\"\"\"");
+    }
+
+    #[test]
+    fn char_combo_counts_the_correct_leading_run() {
+        let mut app = app();
+        app.current_sentence = "Hello world".to_string();
+        assert_eq!(app.char_combo("Hello world"), 11);
+        assert_eq!(app.char_combo("Hellx world"), 4);
+    }
+
+    #[test]
+    fn shuffle_bag_serves_the_whole_pool_before_repeating() {
+        let mut app = app();
+        app.set_seed(5);
+        app.user_data.language = "es".to_string();
+        let pool_size = app.sentences["es"].len();
+        
+        let mut previous = String::new();
+        for _ in 0..3 {
+            let mut cycle = HashSet::new();
+            for _ in 0..pool_size {
+                previous = app.pick_sentence(&previous).1;
+                cycle.insert(previous.clone());
+            }
+            assert_eq!(cycle.len(), pool_size);
+        }
+    }
+
+    #[test]
+    fn numbers_practice_set_always_has_digits() {
+        assert!(TypingApp::is_practice_set("numbers"));
+        let app = app();
+        assert!(app.sentences["numbers"].iter().all(|entry| entry.text.chars().any(|c| c.is_ascii_digit())));
+    }
+
+    #[test]
+    fn averages_are_the_mean_of_the_history() {
+        let mut app = app();
+        assert_eq!(app.average_wpm(), 0.0);
+        app.user_data.session_history = [(20, 90), (25, 95), (36, 100)].iter()
+            .map(|&(wpm, accuracy)| SessionResult { wpm, accuracy, ..SessionResult::default() })
+            .collect();
+        assert_eq!(app.average_wpm(), 27.0);
+        assert_eq!(app.average_accuracy(), 95.0);
+    }
+
+    #[test]
+    fn sequential_order_walks_the_pool_in_order() {
+        let mut app = app();
+        app.user_data.language = "en".to_string();
+        app.user_data.sentence_order = SentenceOrder::Sequential;
+        let pool: Vec<&str> = app.sentences["en"].iter().map(|entry| entry.text).collect();
+        
+        let mut previous = String::new();
+        for expected in pool.iter().chain(pool.iter().take(2)) {
+            previous = app.pick_sentence(&previous).1;
+            assert_eq!(previous, *expected);
+        }
+    }
+
+    #[test]
+    fn percentile_is_the_share_of_slower_sessions() {
+        let mut app = app();
+        assert_eq!(app.wpm_percentile(10), 100.0);
+        app.user_data.session_history = [10, 20, 30, 40].iter()
+            .map(|&wpm| SessionResult { wpm, ..SessionResult::default() })
+            .collect();
+        assert_eq!(app.wpm_percentile(35), 75.0);
+        assert_eq!(app.wpm_percentile(20), 25.0);
+        assert_eq!(app.wpm_percentile(5), 0.0);
+    }
+
+    #[test]
+    fn multi_codepoint_emoji_count_as_one_character() {
+        let mut app = app();
+        app.current_sentence = "Hi 👨‍👩‍👧 👍🏽!".to_string();
+        assert_eq!(graphemes(&app.current_sentence).len(), 7);
+        assert_eq!(app.remaining_text("Hi 👨‍👩‍👧"), " 👍🏽!");
+        // A thumbs-up without its skin tone is a different character
+        assert_eq!(app.char_combo("Hi 👨‍👩‍👧 👍"), 5);
+    }
+
+    #[test]
+    fn case_insensitive_matching_can_be_toggled() {
+        let mut app = app();
+        app.current_sentence = "The Cat".to_string();
+        assert!(!app.is_valid_prefix("the cat"));
+        assert_eq!(app.current_accuracy("the cat"), 5.0 / 7.0 * 100.0);
+        
+        app.user_data.case_insensitive = true;
+        assert!(app.is_valid_prefix("the cat"));
+        assert_eq!(app.current_accuracy("the cat"), 100.0);
+    }
+}
+
+// Paths that touch Date, storage or JS callbacks; run with `wasm-pack test --node`
+#[cfg(all(test, target_arch = "wasm32"))]
+mod wasm_tests {
+    use super::*;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    // Node has no DOM, so the JS hooks become recorders and a Window look-alike
+    // carries an in-memory localStorage for the persistence paths
+    const STUBS: &str = r#"
+        if (!globalThis.__typingAppStubs) {
+            const stubs = { calls: [], items: new Map(), denyStorage: false };
+            for (const name of ["clearCountdownTimer", "startGameTimer", "clearGameTimer",
+                                "clearTypingInput", "advanceSentence", "beginGameSession", "kioskRestart"]) {
+                globalThis[name] = () => stubs.calls.push(name);
+            }
+            globalThis.localStorage = {
+                getItem: (key) => stubs.items.has(key) ? stubs.items.get(key) : null,
+                setItem: (key, value) => {
+                    // Mimics a private-browsing quota that rejects every write
+                    if (stubs.denyStorage) throw new Error("QuotaExceededError");
+                    stubs.items.set(key, String(value));
+                },
+                removeItem: (key) => { stubs.items.delete(key); },
+                key: (index) => [...stubs.items.keys()][index] ?? null,
+                get length() { return stubs.items.size; },
+            };
+            // The bindings expect browser-style numeric timer ids
+            const nodeSetTimeout = globalThis.setTimeout;
+            globalThis.setTimeout = (callback, delay, ...args) => Number(nodeSetTimeout(callback, delay, ...args));
+            globalThis.Window = class Window {};
+            Object.defineProperty(globalThis.Window, Symbol.hasInstance, { value: (obj) => obj === globalThis });
+            globalThis.__typingAppStubs = stubs;
+        }
+        globalThis.__typingAppStubs.calls.length = 0;
+        globalThis.__typingAppStubs.items.clear();
+        globalThis.__typingAppStubs.denyStorage = false;
+    "#;
+
+    fn app() -> TypingApp {
+        js_sys::Function::new_no_args(STUBS).call0(&JsValue::NULL).unwrap();
+        let mut app = TypingApp::new();
+        app.logging_enabled = false;
+        app
+    }
+
+    // Starts a timed session on a known sentence
+    fn start_on(app: &mut TypingApp, sentence: &str) {
+        app.start_game_session();
+        app.set_current_sentence(sentence);
+    }
+
+    // A second app reading back what the first one saved
+    fn reload() -> TypingApp {
+        let mut app = TypingApp::new();
+        app.logging_enabled = false;
+        app.load_user_data();
+        app
+    }
+
+    // A JS callback that appends its first argument to the returned array
+    fn recorder() -> (js_sys::Array, js_sys::Function) {
+        let calls = js_sys::Array::new();
+        let make = js_sys::Function::new_with_args("calls", "return (value) => { calls.push(value); };");
+        let callback = make.call1(&JsValue::NULL, &calls).unwrap();
+        (calls, callback.into())
+    }
+
+    // Clears the recorded JS hook calls and returns the live list that keeps recording
+    fn hook_calls() -> js_sys::Array {
+        let calls: js_sys::Array = js_sys::Function::new_no_args("return globalThis.__typingAppStubs.calls;")
+            .call0(&JsValue::NULL).unwrap().into();
+        calls.set_length(0);
+        calls
+    }
+
+    // Types the whole current sentence with the first `wrong` characters mistyped
+    fn type_sentence(app: &mut TypingApp, wrong: usize) -> String {
+        let typed: String = graphemes(&app.current_sentence.clone()).iter()
+            .enumerate()
+            .map(|(i, unit)| if i < wrong { "#" } else { unit })
+            .collect();
+        app.update_typing_progress(&typed);
+        typed
+    }
+
+    // Runs a scored session straight to its results with the given totals
+    fn play_session(app: &mut TypingApp, typed: usize, correct: usize, seconds: f64) {
+        app.start_game_session();
+        app.session_total_typed_chars = typed;
+        app.session_total_correct_chars = correct;
+        app.session_total_time_spent = seconds;
+        app.end_session();
+    }
+
+    #[wasm_bindgen_test]
+    fn session_result_uses_net_wpm_by_default() {
+        let mut app = app();
+        play_session(&mut app, 100, 80, 60.0);
+        assert_eq!(app.session_wpm(), 16);
+        assert_eq!(app.session_result.as_ref().unwrap().wpm, 16);
+    }
+
+    #[wasm_bindgen_test]
+    fn session_result_uses_gross_wpm_when_selected() {
+        let mut app = app();
+        app.set_wpm_mode(WpmMode::Gross);
+        play_session(&mut app, 100, 80, 60.0);
+        assert_eq!(app.session_result.as_ref().unwrap().wpm, 20);
+    }
+
+    #[wasm_bindgen_test]
+    fn sentences_for_returns_the_whole_pool() {
+        let app = app();
+        let pool: Vec<String> = serde_wasm_bindgen::from_value(app.sentences_for("en")).unwrap();
+        assert_eq!(pool.len(), 15);
+        assert_eq!(pool[0], app.sentences["en"][0].text);
+        
+        let unknown: Vec<String> = serde_wasm_bindgen::from_value(app.sentences_for("xx")).unwrap();
+        assert!(unknown.is_empty());
+    }
+
+    #[wasm_bindgen_test]
+    fn reset_stats_clears_scores_but_keeps_the_player() {
+        let mut app = app();
+        app.set_user_name("Ayu");
+        app.user_data.best_wpm = 42;
+        app.user_data.best_accuracy = 97;
+        app.user_data.total_sessions = 3;
+        app.user_data.best_streak = 5;
+        app.user_data.last_wpm = Some(40);
+        
+        app.reset_stats();
+        assert_eq!(app.user_data.best_wpm, 0);
+        assert_eq!(app.user_data.best_accuracy, 0);
+        assert_eq!(app.user_data.total_sessions, 0);
+        assert_eq!(app.user_data.best_streak, 0);
+        assert_eq!(app.user_data.last_wpm, None);
+        assert_eq!(app.user_name(), "Ayu");
+    }
+
+    #[wasm_bindgen_test]
+    fn reset_all_restores_defaults() {
+        let mut app = app();
+        app.set_user_name("Ayu");
+        app.set_language("en", "English");
+        app.set_duration(60);
+        app.user_data.best_wpm = 42;
+        assert!(app.storage_available());
+        
+        app.reset_all();
+        let defaults = UserData::default();
+        assert_eq!(app.user_name(), defaults.name);
+        assert_eq!(app.current_language(), defaults.language);
+        assert_eq!(app.user_data.duration, defaults.duration);
+        assert_eq!(app.user_data.best_wpm, 0);
+        
+        app.load_user_data();
+        assert_eq!(app.user_name(), "");
+    }
+
+    #[wasm_bindgen_test]
+    fn reset_all_removes_every_stored_key() {
+        let mut app = app();
+        let storage = window().unwrap().local_storage().unwrap().unwrap();
+        for key in ["typingAppUserData", "typingAppUserData:Ayu", "typingAppLeaderboard", "typingAppActiveSession",
+                    "typingAppActiveSession:Ayu", "typingAppProfiles", "typingAppActiveProfile", "otherApp"] {
+            storage.set_item(key, "{}").unwrap();
+        }
+
+        app.reset_all();
+        assert_eq!(storage.length().unwrap(), 1);
+        assert_eq!(storage.key(0).unwrap().as_deref(), Some("otherApp"));
+    }
+
+    #[wasm_bindgen_test]
+    fn beating_the_best_wpm_sets_the_record_flag() {
+        let mut app = app();
+        app.user_data.best_wpm = 20;
+        play_session(&mut app, 125, 125, 60.0);
+        assert!(app.new_wpm_record());
+        assert_eq!(app.user_data.best_wpm, 25);
+    }
+
+    #[wasm_bindgen_test]
+    fn tying_the_best_wpm_is_not_a_record() {
+        let mut app = app();
+        app.user_data.best_wpm = 25;
+        app.user_data.best_accuracy = 100;
+        play_session(&mut app, 125, 125, 60.0);
+        assert!(!app.new_wpm_record());
+        assert!(!app.new_accuracy_record());
+        assert_eq!(app.user_data.best_wpm, 25);
+    }
+
+    #[wasm_bindgen_test]
+    fn countdown_tick_stops_at_zero() {
+        let mut app = app();
+        app.set_go_hold_ms(0);
+        app.start_countdown();
+        for _ in 0..10 {
+            app.countdown_tick();
+        }
+        assert_eq!(app.countdown_value(), 0);
+        assert_eq!(app.app_state, AppState::Playing);
+    }
+
+    #[wasm_bindgen_test]
+    fn practice_sentences_leave_stats_unchanged() {
+        let mut app = app();
+        app.start_practice();
+        assert!(app.is_practice());
+        type_sentence(&mut app, 0);
+        type_sentence(&mut app, 3);
+        assert_eq!(app.session_sentences_completed, 2);
+        
+        app.stop_session();
+        assert_eq!(app.user_data.total_sessions, 0);
+        assert_eq!(app.user_data.best_wpm, 0);
+        assert_eq!(app.user_data.best_streak, 0);
+        assert!(app.user_data.session_history.is_empty());
+        assert!(app.session_result.is_none());
+    }
+
+    #[wasm_bindgen_test]
+    fn raised_max_wpm_lets_fast_sessions_through() {
+        let mut app = app();
+        assert!(!app.set_max_wpm(0));
+        assert!(app.set_max_wpm(500));
+        play_session(&mut app, 2000, 2000, 60.0);
+        assert_eq!(app.session_wpm(), 400);
+    }
+
+    #[wasm_bindgen_test]
+    fn user_data_round_trips_through_export_and_import() {
+        let mut source = app();
+        source.set_user_name("Ayu");
+        source.set_language("en", "English");
+        source.set_duration(60);
+        source.user_data.best_wpm = 33;
+        let exported = source.export_user_data();
+        
+        let mut target = app();
+        assert!(target.import_user_data(&exported));
+        assert_eq!(target.user_name(), "Ayu");
+        assert_eq!(target.current_language(), "en");
+        assert_eq!(target.user_data.duration, 60);
+        assert_eq!(target.user_data.best_wpm, 33);
+        assert_eq!(target.export_user_data(), exported);
+    }
+
+    #[wasm_bindgen_test]
+    fn fresh_user_data_round_trips_through_export_and_import() {
+        let exported = app().export_user_data();
+        let mut target = app();
+        assert!(target.import_user_data(&exported));
+        assert_eq!(target.user_name(), "");
+        assert!(!target.import_user_data(r#"{"name":"A"}"#));
+    }
+
+    #[wasm_bindgen_test]
+    fn import_rejects_malformed_json() {
+        let mut app = app();
+        app.set_user_name("Ayu");
+        assert!(!app.import_user_data("{not json"));
+        assert_eq!(app.user_name(), "Ayu");
+    }
+
+    #[wasm_bindgen_test]
+    fn import_rejects_out_of_range_settings() {
+        let mut app = app();
+        for field in [
+            r#""duration":0"#,
+            r#""countdown_seconds":0"#,
+            r#""completion_threshold":0"#,
+            r#""chars_per_word":0"#,
+            r#""sentences_per_round":0"#,
+            r#""max_wpm":0"#,
+            r#""duration_presets":[]"#,
+            r#""languages":["xx"]"#,
+            r#""avatar":"""#,
+        ] {
+            let json = format!(r#"{{"name":"Al","language":"en",{}}}"#, field);
+            assert!(!app.import_user_data(&json), "accepted {}", field);
+        }
+        assert!(app.import_user_data(r#"{"name":"Al","language":"en","duration":60}"#));
+        assert_eq!(app.user_data.duration, 60);
+    }
+
+    #[wasm_bindgen_test]
+    fn breakdown_has_an_entry_per_completed_sentence() {
+        let mut app = app();
+        app.start_game_session();
+        let first = app.original_sentence.clone();
+        type_sentence(&mut app, 0);
+        let second = app.original_sentence.clone();
+        type_sentence(&mut app, 5);
+        
+        let breakdown: Vec<SentenceResult> = serde_wasm_bindgen::from_value(app.sentence_breakdown()).unwrap();
+        assert_eq!(breakdown.len(), 2);
+        assert_eq!(breakdown[0].sentence, first);
+        assert_eq!(breakdown[0].accuracy, 100);
+        assert_eq!(breakdown[1].sentence, second);
+        assert!(breakdown[1].accuracy < 100 && breakdown[1].accuracy > 80);
+        assert!(breakdown.iter().all(|result| result.time_spent >= 0.0));
+    }
+
+    #[wasm_bindgen_test]
+    fn skipping_counts_skips_but_not_completions() {
+        let mut app = app();
+        app.skip_sentence();
+        assert_eq!(app.sentences_skipped(), 0);
+        
+        app.start_game_session();
+        let before = app.original_sentence.clone();
+        app.skip_sentence();
+        assert_ne!(app.original_sentence, before);
+        app.skip_sentence();
+        assert_eq!(app.sentences_skipped(), 2);
+        assert_eq!(app.session_sentences_completed, 0);
+    }
+
+    #[wasm_bindgen_test]
+    fn skipping_during_the_celebration_cancels_the_pending_advance() {
+        let mut app = app();
+        app.set_auto_advance_delay(1000);
+        app.start_game_session();
+        type_sentence(&mut app, 0);
+        assert!(app.awaiting_advance());
+        
+        app.skip_sentence();
+        assert!(!app.awaiting_advance());
+        assert!(app.advance_timeout_id.is_none());
+        assert_eq!(app.sentences_skipped(), 0);
+        assert_eq!(app.session_sentences_completed, 1);
+    }
+
+    #[wasm_bindgen_test]
+    fn set_duration_accepts_only_the_allowed_range() {
+        let mut app = app();
+        assert!(!app.set_duration(0));
+        assert_eq!(app.user_data.duration, 120);
+        assert!(!app.set_duration(10_000));
+        assert_eq!(app.user_data.duration, 120);
+        assert!(app.set_duration(60));
+        assert_eq!(app.user_data.duration, 60);
+    }
+
+    #[wasm_bindgen_test]
+    fn state_callback_fires_on_proceed_to_language() {
+        let mut app = app();
+        let (calls, callback) = recorder();
+        app.set_state_change_callback(callback);
+        
+        app.proceed_to_language();
+        assert_eq!(calls.length(), 0);
+        
+        app.set_user_name("Ayu");
+        app.proceed_to_language();
+        assert_eq!(calls.length(), 1);
+        assert_eq!(calls.get(0).as_f64(), Some(AppState::Language as u32 as f64));
+    }
+
+    #[wasm_bindgen_test]
+    fn leaderboard_sorts_by_wpm_then_accuracy_and_caps() {
+        let mut app = app();
+        let entry = |name: &str, wpm, accuracy| LeaderboardEntry {
+            name: name.to_string(),
+            wpm,
+            accuracy,
+            timestamp: 0.0,
+        };
+        app.record_leaderboard_entry(entry("slow", 10, 100));
+        app.record_leaderboard_entry(entry("tie-low", 30, 80));
+        app.record_leaderboard_entry(entry("fast", 50, 90));
+        app.record_leaderboard_entry(entry("tie-high", 30, 95));
+        
+        let names: Vec<&str> = app.leaderboard.iter().map(|entry| entry.name.as_str()).collect();
+        assert_eq!(names, ["fast", "tie-high", "tie-low", "slow"]);
+        
+        for wpm in 0..20 {
+            app.record_leaderboard_entry(entry("filler", wpm + 11, 50));
+        }
+        assert_eq!(app.leaderboard.len(), LEADERBOARD_SIZE);
+        assert_eq!(app.leaderboard[0].name, "fast");
+        assert!(app.leaderboard.windows(2).all(|pair| pair[0].wpm >= pair[1].wpm));
+    }
+
+    #[wasm_bindgen_test]
+    fn progress_updates_grow_the_wpm_series() {
+        let mut app = app();
+        app.start_game_session();
+        let units = graphemes(&app.current_sentence.clone()).iter().map(|unit| unit.to_string()).collect::<Vec<_>>();
+        
+        let mut lengths = Vec::new();
+        for typed in 1..=5 {
+            app.update_typing_progress(&units[..typed].concat());
+            let series: Vec<f64> = serde_wasm_bindgen::from_value(app.wpm_series()).unwrap();
+            lengths.push(series.len());
+        }
+        assert_eq!(lengths, [1, 2, 3, 4, 5]);
+    }
+
+    #[wasm_bindgen_test]
+    fn mixed_languages_draw_from_every_pool() {
+        let mut app = app();
+        assert!(!app.set_languages(vec!["en".to_string(), "xx".to_string()]));
+        assert!(app.set_languages(vec!["en".to_string(), "es".to_string()]));
+        assert_eq!(app.user_data.language_name, "English");
+        
+        let mut drawn = HashSet::new();
+        for _ in 0..100 {
+            let (language, sentence) = app.pick_sentence("");
+            assert!(app.sentences[&language].iter().any(|entry| entry.text == sentence));
+            drawn.insert(language);
+        }
+        assert_eq!(drawn, HashSet::from(["en".to_string(), "es".to_string()]));
+    }
+
+    #[wasm_bindgen_test]
+    fn mixing_in_a_character_language_scores_per_character() {
+        let mut app = app();
+        app.set_languages(vec!["en".to_string(), "zh".to_string()]);
+        assert!(app.uses_character_metrics());
+        play_session(&mut app, 100, 100, 60.0);
+        assert!(app.session_result.as_ref().unwrap().is_cpm);
+        assert_eq!(app.session_wpm(), 100);
+    }
+
+    #[wasm_bindgen_test]
+    fn mixed_session_time_is_split_by_sentence_language() {
+        let mut app = app();
+        app.set_languages(vec!["en".to_string(), "es".to_string()]);
+        app.start_game_session();
+        app.sentence_language = "en".to_string();
+        app.add_session_time(20.0);
+        app.sentence_language = "es".to_string();
+        app.add_session_time(10.0);
+        app.end_session();
+        assert_eq!(app.user_data.time_per_language.get("en"), Some(&20.0));
+        assert_eq!(app.user_data.time_per_language.get("es"), Some(&10.0));
+    }
+
+    #[wasm_bindgen_test]
+    fn auto_advance_holds_the_sentence_until_the_delay_ends() {
+        let mut app = app();
+        app.set_auto_advance_delay(500);
+        app.start_game_session();
+        let finished = app.original_sentence.clone();
+        type_sentence(&mut app, 0);
+        assert!(app.awaiting_advance());
+        assert!(app.advance_timeout_id.is_some());
+        assert_eq!(app.original_sentence, finished);
+        
+        // Input during the celebration is ignored
+        let typed = app.typed_chars;
+        app.update_typing_progress("x");
+        assert_eq!(app.typed_chars, typed);
+        
+        app.advance_sentence();
+        assert!(!app.awaiting_advance());
+        assert_ne!(app.original_sentence, finished);
+    }
+
+    #[wasm_bindgen_test]
+    fn auto_advance_after_time_expired_ends_the_session() {
+        let mut app = app();
+        app.set_auto_advance_delay(500);
+        app.start_game_session();
+        type_sentence(&mut app, 0);
+        app.session_start_time = Some(Date::now() - 1000.0 * (app.user_data.duration as f64 + 1.0));
+        app.advance_sentence();
+        assert_eq!(app.app_state, AppState::Results);
+    }
+
+    #[wasm_bindgen_test]
+    fn zero_delay_advances_immediately() {
+        let mut app = app();
+        app.start_game_session();
+        let finished = app.original_sentence.clone();
+        type_sentence(&mut app, 0);
+        assert!(!app.awaiting_advance());
+        assert_ne!(app.original_sentence, finished);
+    }
+
+    #[wasm_bindgen_test]
+    fn sentence_elapsed_counts_from_the_start_time() {
+        let mut app = app();
+        assert_eq!(app.current_sentence_elapsed(), 0.0);
+        app.start_time = Some(Date::now() - 5000.0);
+        let elapsed = app.current_sentence_elapsed();
+        assert!((5.0..5.5).contains(&elapsed), "elapsed {}", elapsed);
+    }
+
+    #[wasm_bindgen_test]
+    fn short_sessions_do_not_set_the_best_accuracy() {
+        let mut app = app();
+        play_session(&mut app, 5, 5, 10.0);
+        assert!(!app.new_accuracy_record());
+        assert_eq!(app.user_data.best_accuracy, 0);
+        
+        play_session(&mut app, 50, 50, 60.0);
+        assert!(app.new_accuracy_record());
+        assert_eq!(app.user_data.best_accuracy, 100);
+    }
+
+    #[wasm_bindgen_test]
+    fn previewed_sentence_becomes_the_next_one() {
+        let mut app = app();
+        app.start_game_session();
+        for _ in 0..5 {
+            let preview = app.next_sentence().unwrap();
+            app.generate_new_sentence();
+            assert_eq!(app.original_sentence, preview);
+        }
+    }
+
+    #[wasm_bindgen_test]
+    fn countdown_starts_at_the_configured_length() {
+        let mut app = app();
+        assert!(app.set_countdown_seconds(3));
+        app.start_countdown();
+        assert_eq!(app.countdown_value(), 3);
+        app.countdown_tick();
+        assert_eq!(app.countdown_value(), 2);
+    }
+
+    #[wasm_bindgen_test]
+    fn perfect_sentences_build_the_streak_and_a_typo_resets_it() {
+        let mut app = app();
+        app.start_game_session();
+        type_sentence(&mut app, 0);
+        type_sentence(&mut app, 0);
+        assert_eq!(app.current_streak(), 2);
+        assert_eq!(app.best_streak(), 2);
+        
+        type_sentence(&mut app, 1);
+        assert_eq!(app.current_streak(), 0);
+        assert_eq!(app.best_streak(), 2);
+    }
+
+    #[wasm_bindgen_test]
+    fn strict_mode_blocks_completion_until_the_typo_is_fixed() {
+        let mut app = app();
+        app.set_strict_mode(true);
+        app.start_game_session();
+        let sentence = app.original_sentence.clone();
+        let units: Vec<String> = graphemes(&sentence).iter().map(|unit| unit.to_string()).collect();
+        
+        // A typo in the middle stays in place while the rest is typed out
+        let middle = units.len() / 2;
+        let mut typed = units.clone();
+        typed[middle] = "#".to_string();
+        app.update_typing_progress(&typed.concat());
+        app.update_typing_progress(&format!("{}{}", typed.concat(), "extra"));
+        assert_eq!(app.session_sentences_completed, 0);
+        assert_eq!(app.original_sentence, sentence);
+        
+        app.update_typing_progress(&units.concat());
+        assert_eq!(app.session_sentences_completed, 1);
+    }
+
+    #[wasm_bindgen_test]
+    fn strict_mode_only_blocks_past_a_mismatch() {
+        let mut app = app();
+        app.set_strict_mode(true);
+        start_on(&mut app, "abcd");
+        let result: HashMap<String, f64> = serde_wasm_bindgen::from_value(app.update_typing_progress("abX")).unwrap();
+        assert_eq!(result["blocked_at_index"], 2.0);
+        
+        let result: HashMap<String, f64> = serde_wasm_bindgen::from_value(app.update_typing_progress("abcdX")).unwrap();
+        assert_eq!(result["blocked_at_index"], -1.0);
+        assert_eq!(result["is_complete"], 1.0);
+    }
+
+    #[wasm_bindgen_test]
+    fn keystrokes_call_the_matching_sound() {
+        let mut app = app();
+        let (correct, correct_callback) = recorder();
+        let (errors, error_callback) = recorder();
+        app.set_correct_sound(correct_callback);
+        app.set_error_sound(error_callback);
+        app.start_game_session();
+        
+        let first = graphemes(&app.current_sentence.clone())[0].to_string();
+        app.update_typing_progress(&first);
+        app.update_typing_progress(&format!("{}#", first));
+        assert_eq!(correct.length(), 1);
+        assert_eq!(errors.length(), 1);
+    }
+
+    #[wasm_bindgen_test]
+    fn projection_at_the_buzzer_equals_the_current_wpm() {
+        let mut app = app();
+        app.set_duration(60);
+        app.session_start_time = Some(Date::now() - 60_000.0);
+        app.session_total_typed_chars = 125;
+        app.session_total_correct_chars = 125;
+        app.session_total_time_spent = 60.0;
+        assert!((app.projected_wpm() - app.calculate_session_wpm()).abs() < 1e-9);
+        assert_eq!(app.calculate_session_wpm(), 25.0);
+    }
+
+    #[wasm_bindgen_test]
+    fn projection_mid_session_extrapolates_the_pace() {
+        let mut app = app();
+        app.set_duration(60);
+        app.session_start_time = Some(Date::now() - 30_000.0);
+        app.session_total_correct_chars = 50;
+        // 50 chars in 30s projects to 100 chars, or 20 words, over the minute
+        let projected = app.projected_wpm();
+        assert!((19.5..=20.0).contains(&projected), "projected {}", projected);
+    }
+
+    #[wasm_bindgen_test]
+    fn replay_serves_the_same_sentences_in_order() {
+        let mut app = app();
+        app.start_game_session();
+        type_sentence(&mut app, 0);
+        type_sentence(&mut app, 0);
+        let played: Vec<String> = app.played_sentences.iter().map(|(_, sentence)| sentence.clone()).collect();
+        app.end_session();
+        
+        app.replay_session();
+        app.start_game_session();
+        let mut replayed = vec![app.original_sentence.clone()];
+        for _ in 1..played.len() {
+            type_sentence(&mut app, 0);
+            replayed.push(app.original_sentence.clone());
+        }
+        assert_eq!(replayed, played);
+    }
+
+    #[wasm_bindgen_test]
+    fn time_accumulates_per_language_across_sessions() {
+        let mut app = app();
+        app.set_language("en", "English");
+        play_session(&mut app, 100, 100, 30.0);
+        app.set_language("es", "Español");
+        play_session(&mut app, 100, 100, 20.0);
+        app.set_language("en", "English");
+        play_session(&mut app, 100, 100, 10.0);
+        assert_eq!(app.user_data.time_per_language.get("en"), Some(&40.0));
+        assert_eq!(app.user_data.time_per_language.get("es"), Some(&20.0));
+    }
+
+    #[wasm_bindgen_test]
+    fn rejected_storage_keeps_progress_in_memory() {
+        let mut app = app();
+        js_sys::Function::new_no_args("globalThis.__typingAppStubs.denyStorage = true;").call0(&JsValue::NULL).unwrap();
+        assert!(app.set_user_name("Ayu"));
+        assert!(!app.storage_available());
+        assert_eq!(app.user_name(), "Ayu");
+        play_session(&mut app, 100, 100, 60.0);
+        assert_eq!(app.user_data.total_sessions, 1);
+    }
+
+    #[wasm_bindgen_test]
+    fn session_complete_callback_receives_the_result() {
+        let mut app = app();
+        let (calls, callback) = recorder();
+        app.set_session_complete_callback(callback);
+        play_session(&mut app, 100, 80, 60.0);
+        
+        assert_eq!(calls.length(), 1);
+        let result: SessionResult = serde_wasm_bindgen::from_value(calls.get(0)).unwrap();
+        assert_eq!(result.wpm, 16);
+        assert_eq!(result.accuracy, 80);
+    }
+
+    #[wasm_bindgen_test]
+    fn progress_is_frozen_while_paused() {
+        let mut app = app();
+        app.start_game_session();
+        app.session_start_time = Some(Date::now() - 10_000.0);
+        app.pause();
+        let time_progress = app.time_progress();
+        
+        let first = graphemes(&app.current_sentence.clone())[0].to_string();
+        app.update_typing_progress(&first);
+        assert_eq!(app.typed_chars, 0);
+        
+        // Five more seconds pass on the wall clock without moving the session clock
+        app.session_start_time = app.session_start_time.map(|start| start - 5_000.0);
+        app.pause_started_at = app.pause_started_at.map(|paused_at| paused_at - 5_000.0);
+        assert!((app.time_progress() - time_progress).abs() < 1e-3);
+    }
+
+    #[wasm_bindgen_test]
+    fn available_languages_matches_the_sentence_pools() {
+        let app = app();
+        let languages: Vec<LanguageInfo> = serde_wasm_bindgen::from_value(app.available_languages()).unwrap();
+        let codes: HashSet<String> = languages.iter().map(|language| language.code.clone()).collect();
+        let keys: HashSet<String> = app.sentences.keys().cloned().collect();
+        assert_eq!(codes, keys);
+        assert_eq!(languages.len(), app.sentences.len());
+    }
+
+    #[wasm_bindgen_test]
+    fn falling_short_of_the_target_is_not_a_met_goal() {
+        let mut app = app();
+        app.set_target_wpm(30);
+        play_session(&mut app, 125, 125, 60.0);
+        assert_eq!(app.session_wpm(), 25);
+        assert!(!app.goal_met());
+        
+        play_session(&mut app, 150, 150, 60.0);
+        assert!(app.goal_met());
+    }
+
+    #[wasm_bindgen_test]
+    fn an_idle_gap_auto_pauses() {
+        let mut app = app();
+        app.set_idle_timeout(5_000);
+        app.start_game_session();
+        assert!(!app.check_idle());
+        
+        app.last_activity_time = Some(Date::now() - 6_000.0);
+        assert!(app.check_idle());
+        assert!(app.paused);
+        assert!(!app.check_idle());
+    }
+
+    #[wasm_bindgen_test]
+    fn second_session_reports_deltas_against_the_first() {
+        let mut app = app();
+        play_session(&mut app, 100, 90, 60.0);
+        assert!(!app.session_result.as_ref().unwrap().has_previous_session);
+        assert_eq!(app.wpm_delta(), 0);
         
-        // Update user data
-        self.user_data.total_sessions += 1;
-        if wpm as u32 > self.user_data.best_wpm {
-            self.user_data.best_wpm = wpm as u32;
-        }
-        if accuracy as u32 > self.user_data.best_accuracy {
-            self.user_data.best_accuracy = accuracy as u32;
-        }
+        play_session(&mut app, 150, 150, 60.0);
+        assert!(app.session_result.as_ref().unwrap().has_previous_session);
+        assert_eq!(app.wpm_delta(), 12);
+        assert_eq!(app.accuracy_delta(), 10);
+    }
+
+    #[wasm_bindgen_test]
+    fn session_flags_follow_the_lifecycle() {
+        let mut app = app();
+        assert!(!app.is_playing());
+        assert!(!app.is_session_active());
         
-        self.save_user_data();
+        app.start_game_session();
+        assert!(app.is_playing());
+        assert!(!app.is_session_active());
         
-        console_log!("Session ended, showing results directly. WPM: {}, Accuracy: {}%", wpm as u32, accuracy as u32);
+        let first = graphemes(&app.current_sentence.clone())[0].to_string();
+        app.update_typing_progress(&first);
+        assert!(app.is_session_active());
         
-        // Show results directly without time's up screen
-        self.show_results();
+        app.end_session();
+        assert!(!app.is_playing());
+        assert!(!app.is_session_active());
     }
 
-    #[wasm_bindgen]
-    pub fn show_results(&mut self) {
-        console_log!("show_results called, transitioning to results screen");
-        self.app_state = AppState::Results;
-        self.show_screen("results-screen");
-        self.display_results();
-        console_log!("Results screen displayed successfully");
+    #[wasm_bindgen_test]
+    fn disabled_logging_stays_off_the_console() {
+        let mut app = app();
+        let (calls, callback) = recorder();
+        let console = js_sys::Reflect::get(&js_sys::global(), &"console".into()).unwrap();
+        let original = js_sys::Reflect::get(&console, &"log".into()).unwrap();
+        js_sys::Reflect::set(&console, &"log".into(), &callback).unwrap();
+        
+        app.set_logging_enabled(false);
+        app.test_connection();
+        let silent = calls.length();
+        app.set_logging_enabled(true);
+        app.test_connection();
+        let logged = calls.length();
+        
+        js_sys::Reflect::set(&console, &"log".into(), &original).unwrap();
+        assert_eq!(silent, 0);
+        assert_eq!(logged, 1);
     }
 
-    #[wasm_bindgen]
-    pub fn restart_game(&mut self) {
-        self.start_countdown();
+    #[wasm_bindgen_test]
+    fn chinese_projection_is_per_character() {
+        let mut app = app();
+        app.set_language("zh", "中文");
+        app.set_duration(60);
+        app.session_start_time = Some(Date::now() - 30_000.0);
+        app.session_total_correct_chars = 40;
+        // 40 characters in 30s projects to 80 characters over the minute
+        let projected = app.projected_wpm();
+        assert!((79.0..=80.0).contains(&projected), "projected {}", projected);
     }
 
-    #[wasm_bindgen]
-    pub fn change_settings(&mut self) {
-        self.show_screen("language-screen");
-        self.app_state = AppState::Language;
+    #[wasm_bindgen_test]
+    fn goto_game_screen_enters_playing() {
+        let mut app = app();
+        assert!(app.goto_screen("game-screen"));
+        assert_eq!(app.app_state, AppState::Playing);
+        assert!(!app.goto_screen("no-such-screen"));
+        assert_eq!(app.app_state, AppState::Playing);
     }
 
-    #[wasm_bindgen]
-    pub fn new_session(&mut self) {
-        self.show_screen("welcome-screen");
-        self.app_state = AppState::Welcome;
+    #[wasm_bindgen_test]
+    fn time_progress_is_the_elapsed_share_of_the_duration() {
+        let mut app = app();
+        app.set_duration(60);
+        assert_eq!(app.time_progress(), 0.0);
+        app.session_start_time = Some(Date::now() - 15_000.0);
+        assert!((app.time_progress() - 0.25).abs() < 0.01);
+        app.session_start_time = Some(Date::now() - 90_000.0);
+        assert_eq!(app.time_progress(), 1.0);
     }
 
-    // Calculation methods
-    fn calculate_session_wpm(&self) -> f64 {
-        if self.session_total_typed_chars == 0 || self.session_total_time_spent == 0.0 {
-            return 0.0;
-        }
+    #[wasm_bindgen_test]
+    fn pause_and_resume_return_to_playing() {
+        let mut app = app();
+        let before = app.app_state;
+        app.pause();
+        assert_eq!(app.app_state, before);
+        assert!(!app.paused);
         
-        let words = (self.session_total_typed_chars as f64) / 5.0;
-        let minutes = self.session_total_time_spent / 60.0;
-        (words / minutes).min(300.0).max(0.0)
+        app.start_game_session();
+        app.pause();
+        assert_eq!(app.app_state, AppState::Paused);
+        app.resume();
+        assert_eq!(app.app_state, AppState::Playing);
+        assert!(!app.paused);
     }
 
-    fn calculate_session_accuracy(&self) -> f64 {
-        if self.session_total_typed_chars == 0 {
-            return 100.0;
-        }
+    #[wasm_bindgen_test]
+    fn resuming_practice_leaves_the_game_timer_alone() {
+        let mut app = app();
+        app.start_practice();
+        app.pause();
+        assert_eq!(app.app_state, AppState::Paused);
         
-        ((self.session_total_correct_chars as f64) / (self.session_total_typed_chars as f64) * 100.0)
-            .min(100.0).max(0.0)
+        let calls = hook_calls();
+        app.resume();
+        assert_eq!(app.app_state, AppState::Practice);
+        let calls: Vec<String> = serde_wasm_bindgen::from_value(calls.into()).unwrap();
+        assert!(!calls.contains(&"startGameTimer".to_string()));
     }
 
-    fn is_time_expired(&self) -> bool {
-        if let Some(session_start) = self.session_start_time {
-            let elapsed = (Date::now() - session_start) / 1000.0;
-            elapsed >= (self.user_data.duration as f64)
-        } else {
-            false
-        }
+    #[wasm_bindgen_test]
+    fn session_typed_chars_includes_the_sentence_in_progress() {
+        let mut app = app();
+        app.start_game_session();
+        let finished = graphemes(&app.current_sentence.clone()).len();
+        type_sentence(&mut app, 0);
+        assert_eq!(app.session_typed_chars() as usize, finished);
+        
+        let partial: String = graphemes(&app.current_sentence.clone())[..4].concat();
+        app.update_typing_progress(&partial);
+        assert_eq!(app.session_typed_chars() as usize, finished + 4);
     }
 
-    fn get_remaining_time(&self) -> f64 {
-        if let Some(session_start) = self.session_start_time {
-            let elapsed = (Date::now() - session_start) / 1000.0;
-            ((self.user_data.duration as f64) - elapsed).max(0.0)
-        } else {
-            self.user_data.duration as f64
-        }
+    #[wasm_bindgen_test]
+    fn shrinking_input_counts_as_a_backspace() {
+        let mut app = app();
+        app.start_game_session();
+        let units: Vec<String> = graphemes(&app.current_sentence.clone()).iter().map(|unit| unit.to_string()).collect();
+        app.update_typing_progress(&units[..3].concat());
+        app.update_typing_progress(&units[..2].concat());
+        app.update_typing_progress(&units[..3].concat());
+        app.update_typing_progress(&units[..1].concat());
+        assert_eq!(app.backspace_count(), 2);
     }
 
-    fn create_progress_result(&self, is_complete: bool, time_expired: bool) -> JsValue {
-        let mut result = HashMap::new();
-        result.insert("correct_chars".to_string(), self.correct_chars as f64);
-        result.insert("typed_chars".to_string(), self.typed_chars as f64);
-        result.insert("wpm".to_string(), self.calculate_current_wpm());
-        result.insert("accuracy".to_string(), self.calculate_current_accuracy());
-        result.insert("is_complete".to_string(), if is_complete { 1.0 } else { 0.0 });
-        result.insert("time_expired".to_string(), if time_expired { 1.0 } else { 0.0 });
-        result.insert("remaining_time".to_string(), self.get_remaining_time());
+    #[wasm_bindgen_test]
+    fn duration_presets_round_trip_through_storage() {
+        let mut app = app();
+        assert!(!app.set_duration_presets("[0, 60]"));
+        assert!(app.set_duration_presets("[180, 30, 60, 30]"));
+        let presets: Vec<u32> = serde_wasm_bindgen::from_value(app.duration_presets()).unwrap();
+        assert_eq!(presets, [30, 60, 180]);
         
-        serde_wasm_bindgen::to_value(&result).unwrap_or(JsValue::NULL)
+        let reloaded = reload();
+        let presets: Vec<u32> = serde_wasm_bindgen::from_value(reloaded.duration_presets()).unwrap();
+        assert_eq!(presets, [30, 60, 180]);
     }
 
-    fn calculate_current_wpm(&self) -> f64 {
-        if self.typed_chars == 0 || self.start_time.is_none() {
-            return 0.0;
+    #[wasm_bindgen_test]
+    fn error_sound_fires_once_within_the_cooldown() {
+        let mut app = app();
+        let (errors, callback) = recorder();
+        app.set_error_sound(callback);
+        app.set_error_sound_cooldown(10_000);
+        app.start_game_session();
+        app.update_typing_progress("#");
+        app.update_typing_progress("##");
+        app.update_typing_progress("###");
+        assert_eq!(errors.length(), 1);
+        
+        app.last_error_sound_time = Some(Date::now() - 20_000.0);
+        app.update_typing_progress("####");
+        assert_eq!(errors.length(), 2);
+    }
+
+    #[wasm_bindgen_test]
+    fn player_summary_reports_name_and_bests() {
+        let mut app = app();
+        app.set_user_name("Ayu");
+        play_session(&mut app, 125, 125, 60.0);
+        let summary: PlayerSummary = serde_wasm_bindgen::from_value(app.player_summary()).unwrap();
+        assert_eq!(summary.name, "Ayu");
+        assert_eq!(summary.best_wpm, 25);
+        assert_eq!(summary.best_accuracy, 100);
+        assert_eq!(summary.total_sessions, 1);
+        assert_eq!(summary.best_streak, 0);
+    }
+
+    #[wasm_bindgen_test]
+    fn a_sentence_past_the_buzzer_counts_only_up_to_the_duration() {
+        let mut app = app();
+        app.set_duration(60);
+        app.start_game_session();
+        app.session_total_time_spent = 55.0;
+        let units: Vec<String> = graphemes(&app.current_sentence.clone()).iter().map(|unit| unit.to_string()).collect();
+        app.update_typing_progress(&units[0]);
+        app.start_time = Some(Date::now() - 30_000.0);
+        app.update_typing_progress(&units.concat());
+        assert_eq!(app.session_total_time_spent, 60.0);
+    }
+
+    #[wasm_bindgen_test]
+    fn ending_mid_sentence_is_flagged_incomplete() {
+        let mut app = app();
+        app.start_game_session();
+        type_sentence(&mut app, 0);
+        app.end_session();
+        assert!(!app.ended_incomplete());
+        
+        app.start_game_session();
+        let first = graphemes(&app.current_sentence.clone())[0].to_string();
+        app.update_typing_progress(&first);
+        app.end_session();
+        assert!(app.ended_incomplete());
+    }
+
+    #[wasm_bindgen_test]
+    fn go_hold_defers_the_start_until_it_ends() {
+        let mut app = app();
+        app.set_go_hold_ms(1000);
+        app.start_countdown();
+        for _ in 0..app.countdown_value() {
+            app.countdown_tick();
         }
+        assert_eq!(app.app_state, AppState::Countdown);
+        assert!(app.go_timeout_id.is_some());
         
-        if let Some(start) = self.start_time {
-            let elapsed_ms = Date::now() - start;
-            if elapsed_ms < 1000.0 {
-                return 0.0;
+        app.start_game_session();
+        assert_eq!(app.app_state, AppState::Playing);
+        assert!(app.go_timeout_id.is_none());
+    }
+
+    #[wasm_bindgen_test]
+    fn leaving_during_the_go_hold_cancels_the_start() {
+        let mut app = app();
+        app.set_go_hold_ms(1000);
+        for leave in [TypingApp::change_settings, TypingApp::new_session] {
+            app.start_countdown();
+            for _ in 0..app.countdown_value() {
+                app.countdown_tick();
             }
+            assert!(app.go_timeout_id.is_some());
             
-            let elapsed_minutes = elapsed_ms / 60000.0;
-            let words = (self.typed_chars as f64) / 5.0;
-            (words / elapsed_minutes).min(300.0).max(0.0)
-        } else {
-            0.0
+            leave(&mut app);
+            assert!(app.go_timeout_id.is_none());
+            assert_ne!(app.app_state, AppState::Playing);
         }
     }
 
-    fn calculate_current_accuracy(&self) -> f64 {
-        if self.typed_chars == 0 {
-            return 100.0;
-        }
+    #[wasm_bindgen_test]
+    fn avatar_survives_a_save_and_reload() {
+        let mut app = app();
+        assert!(!app.set_avatar("   "));
+        assert!(app.set_avatar("🦊"));
         
-        ((self.correct_chars as f64) / (self.typed_chars as f64) * 100.0).min(100.0).max(0.0)
+        let reloaded = reload();
+        assert_eq!(reloaded.avatar(), "🦊");
     }
 
-    // UI helper methods
-    fn show_screen(&self, screen_id: &str) {
-        if let Some(window) = window() {
-            if let Some(document) = window.document() {
-                // Hide all screens
-                let screens = ["welcome-screen", "language-screen", "timer-screen", 
-                             "countdown-screen", "game-screen", "times-up-screen", "results-screen"];
-                
-                for screen in &screens {
-                    if let Some(element) = document.get_element_by_id(screen) {
-                        element.set_class_name("screen hidden");
-                    }
-                }
-                
-                // Show target screen
-                if let Some(element) = document.get_element_by_id(screen_id) {
-                    element.set_class_name("screen active");
-                }
-                
-                self.update_personalization();
-            }
-        }
+    #[wasm_bindgen_test]
+    fn first_profile_keeps_the_original_player_reachable() {
+        let mut app = app();
+        app.set_user_name("Ayu");
+        play_session(&mut app, 150, 150, 60.0);
+        
+        assert!(app.create_profile("Budi"));
+        let profiles: Vec<String> = serde_wasm_bindgen::from_value(app.list_profiles()).unwrap();
+        assert_eq!(profiles, ["Ayu", "Budi"]);
+        assert_eq!(app.user_name(), "Budi");
+        assert_eq!(app.user_data.best_wpm, 0);
+        
+        assert!(app.switch_profile("Ayu"));
+        assert_eq!(app.user_name(), "Ayu");
+        assert_eq!(app.user_data.best_wpm, 30);
+        
+        assert!(app.switch_profile("Budi"));
+        assert_eq!(app.user_name(), "Budi");
+        assert_eq!(app.user_data.best_wpm, 0);
     }
 
-    fn update_personalization(&self) {
-        if let Some(window) = window() {
-            if let Some(document) = window.document() {
-                // Update name displays
-                let name_elements = ["player-name-display", "player-name-timer", 
-                                   "player-name-countdown", "player-name-game", 
-                                   "player-name-times-up", "results-player-name"];
-                
-                for element_id in &name_elements {
-                    if let Some(element) = document.get_element_by_id(element_id) {
-                        element.set_text_content(Some(&self.user_data.name));
-                    }
-                }
-                
-                // Update language displays
-                if let Some(element) = document.get_element_by_id("selected-language-display") {
-                    element.set_text_content(Some(&self.user_data.language_name));
-                }
-                
-                // Update duration displays
-                if let Some(element) = document.get_element_by_id("selected-duration-display") {
-                    let duration_text = self.format_duration(self.user_data.duration);
-                    element.set_text_content(Some(&duration_text));
-                }
-            }
-        }
+    #[wasm_bindgen_test]
+    fn pinning_a_sentence_cancels_the_pending_advance() {
+        let mut app = app();
+        app.set_auto_advance_delay(1000);
+        app.start_game_session();
+        type_sentence(&mut app, 0);
+        assert!(app.awaiting_advance());
+        
+        assert!(app.set_current_sentence("Type this first."));
+        assert!(!app.awaiting_advance());
+        assert!(app.advance_timeout_id.is_none());
+        app.advance_sentence();
+        assert_eq!(app.original_sentence, "Type this first.");
     }
 
-    fn format_duration(&self, seconds: u32) -> String {
-        if seconds >= 60 {
-            let minutes = seconds / 60;
-            format!("{} minute{}", minutes, if minutes != 1 { "s" } else { "" })
-        } else {
-            format!("{} seconds", seconds)
-        }
+    #[wasm_bindgen_test]
+    fn progress_reports_the_first_error_index() {
+        let mut app = app();
+        app.start_game_session();
+        let mut typed: Vec<String> = graphemes(&app.current_sentence.clone())[..6].iter().map(|unit| unit.to_string()).collect();
+        let clean: HashMap<String, f64> = serde_wasm_bindgen::from_value(app.update_typing_progress(&typed.concat())).unwrap();
+        assert_eq!(clean["first_error_index"], -1.0);
+        
+        typed[3] = "#".to_string();
+        typed[5] = "#".to_string();
+        let result: HashMap<String, f64> = serde_wasm_bindgen::from_value(app.update_typing_progress(&typed.concat())).unwrap();
+        assert_eq!(result["first_error_index"], 3.0);
     }
 
-    fn display_sentence(&self, sentence: &str) {
-        if let Some(window) = window() {
-            if let Some(document) = window.document() {
-                if let Some(sentence_el) = document.get_element_by_id("sentence") {
-                    sentence_el.set_inner_html("");
-                    
-                    for (i, ch) in sentence.chars().enumerate() {
-                        if let Some(span) = document.create_element("span").ok() {
-                            span.set_text_content(Some(&ch.to_string()));
-                            span.set_class_name("char");
-                            span.set_attribute("data-index", &i.to_string()).ok();
-                            sentence_el.append_child(&span).ok();
-                        }
-                    }
-                }
-            }
-        }
+    #[wasm_bindgen_test]
+    fn kiosk_results_schedule_a_restart() {
+        let mut app = app();
+        play_session(&mut app, 100, 100, 60.0);
+        assert!(app.kiosk_timeout_id.is_none());
+        
+        app.set_kiosk_mode(true);
+        play_session(&mut app, 100, 100, 60.0);
+        assert_eq!(app.app_state, AppState::Results);
+        assert!(app.kiosk_timeout_id.is_some());
+        
+        // Leaving the results screen by hand cancels the scheduled round
+        app.change_settings();
+        assert!(app.kiosk_timeout_id.is_none());
     }
 
-    fn update_countdown_display(&self) {
-        if let Some(window) = window() {
-            if let Some(document) = window.document() {
-                if let Some(element) = document.get_element_by_id("countdown-number") {
-                    element.set_text_content(Some(&self.countdown_value.to_string()));
-                }
-            }
+    #[wasm_bindgen_test]
+    fn words_typed_sums_completed_sentences() {
+        let mut app = app();
+        app.start_game_session();
+        let mut expected = 0;
+        for _ in 0..3 {
+            expected += type_sentence(&mut app, 0).split_whitespace().count();
         }
+        app.end_session();
+        assert_eq!(app.session_result.as_ref().unwrap().words_typed as usize, expected);
     }
 
-    fn update_countdown_message(&self) {
-        let messages = [
-            "GO! Help our magical friend! 🌟",                    // 0
-            "Remember: slow and steady wins the race! 🐢",        // 1  
-            "Take your time and be careful! 🎯",                  // 2
-            "Put your hands on the keyboard like a pianist! 🎹", // 3
-            "Get ready for a magical adventure! ✨"               // 4
-        ];
+    #[wasm_bindgen_test]
+    fn leading_spaces_are_ignored_when_trimming() {
+        let mut app = app();
+        start_on(&mut app, "Once upon a time.");
+        app.update_typing_progress("  Once");
+        assert_eq!((app.typed_chars, app.correct_chars), (6, 0));
         
-        if let Some(window) = window() {
-            if let Some(document) = window.document() {
-                if let Some(element) = document.get_element_by_id("countdown-text") {
-                    let message_index = self.countdown_value as usize;
-                    if message_index < messages.len() {
-                        element.set_text_content(Some(messages[message_index]));
-                    }
-                }
-            }
-        }
+        app.set_trim_leading_space(true);
+        app.set_current_sentence("Once upon a time.");
+        app.update_typing_progress("  Once");
+        assert_eq!((app.typed_chars, app.correct_chars), (4, 4));
     }
 
-    fn display_results(&self) {
-        console_log!("display_results called");
-        if let Some(result) = &self.session_result {
-            console_log!("Session result found: WPM={}, Accuracy={}%, Chars={}, Sentences={}", 
-                        result.wpm, result.accuracy, result.typed_chars, result.sentences_completed);
-            
-            if let Some(window) = window() {
-                if let Some(document) = window.document() {
-                    // Update final stats
-                    if let Some(element) = document.get_element_by_id("final-wpm") {
-                        element.set_text_content(Some(&result.wpm.to_string()));
-                        console_log!("Updated final-wpm: {}", result.wpm);
-                    }
-                    if let Some(element) = document.get_element_by_id("final-accuracy") {
-                        element.set_text_content(Some(&format!("{}%", result.accuracy)));
-                        console_log!("Updated final-accuracy: {}%", result.accuracy);
-                    }
-                    if let Some(element) = document.get_element_by_id("total-characters") {
-                        element.set_text_content(Some(&result.typed_chars.to_string()));
-                        console_log!("Updated total-characters: {}", result.typed_chars);
-                    }
-                    if let Some(element) = document.get_element_by_id("sentences-completed") {
-                        element.set_text_content(Some(&result.sentences_completed.to_string()));
-                        console_log!("Updated sentences-completed: {}", result.sentences_completed);
-                    }
-                } else {
-                    console_log!("Document not found");
-                }
-            } else {
-                console_log!("Window not found");
-            }
-        } else {
-            console_log!("No session result found to display");
-        }
+    #[wasm_bindgen_test]
+    fn interrupted_session_resumes_only_for_its_own_profile() {
+        let mut app = app();
+        assert!(app.create_profile("Ayu"));
+        assert!(app.create_profile("Budi"));
+        assert!(app.switch_profile("Ayu"));
+        app.start_game_session();
+        type_sentence(&mut app, 0);
+        let resumed_sentence = app.original_sentence.clone();
+        
+        // A reload lands on whichever profile was last active
+        let reload = |profile: &str| {
+            let mut reloaded = TypingApp::new();
+            reloaded.logging_enabled = false;
+            reloaded.load_profiles();
+            reloaded.switch_profile(profile);
+            reloaded
+        };
+        let mut other = reload("Budi");
+        assert!(!other.resume_active_session());
+        
+        let mut same = reload("Ayu");
+        assert!(same.resume_active_session());
+        assert_eq!(same.original_sentence, resumed_sentence);
+        assert_eq!(same.session_sentences_completed, 1);
     }
 
-    // Data persistence
-    fn save_user_data(&self) {
-        if let Some(window) = window() {
-            if let Some(storage) = window.local_storage().ok().flatten() {
-                if let Ok(serialized) = serde_json::to_string(&self.user_data) {
-                    storage.set_item("typingAppUserData", &serialized).ok();
-                }
-            }
+    #[wasm_bindgen_test]
+    fn threshold_completes_a_sentence_at_eighty_percent() {
+        let mut app = app();
+        assert!(app.set_completion_threshold(0.8));
+        start_on(&mut app, "abcdefghij");
+        app.update_typing_progress("abcdefg");
+        assert_eq!(app.session_sentences_completed, 0);
+        app.update_typing_progress("abcdefgh");
+        assert_eq!(app.session_sentences_completed, 1);
+    }
+
+    #[wasm_bindgen_test]
+    fn accuracy_trend_lists_each_sentence_in_order() {
+        let mut app = app();
+        start_on(&mut app, "abcdefghij");
+        app.update_typing_progress("abcdefghij");
+        app.set_current_sentence("abcdefghij");
+        app.update_typing_progress("abcdeXXXXX");
+        let trend: Vec<u32> = serde_wasm_bindgen::from_value(app.accuracy_trend()).unwrap();
+        assert_eq!(trend, [100, 50]);
+    }
+
+    #[wasm_bindgen_test]
+    fn efficiency_is_correct_chars_per_keystroke() {
+        let mut app = app();
+        assert_eq!(app.typing_efficiency(), 100.0);
+        start_on(&mut app, "abcd");
+        // Five keystrokes, one of them a correction, leave three correct characters
+        for typed in ["a", "ax", "a", "ab", "abc"] {
+            app.update_typing_progress(typed);
         }
+        assert_eq!(app.typing_efficiency(), 60.0);
     }
 
-    fn load_user_data(&mut self) {
-        if let Some(window) = window() {
-            if let Some(storage) = window.local_storage().ok().flatten() {
-                if let Ok(Some(data)) = storage.get_item("typingAppUserData") {
-                    if let Ok(user_data) = serde_json::from_str::<UserData>(&data) {
-                        self.user_data = user_data;
-                    }
-                }
-            }
+    #[wasm_bindgen_test]
+    fn manual_generation_waits_for_the_embedder() {
+        let mut app = app();
+        app.set_auto_generate(false);
+        start_on(&mut app, "First one.");
+        app.update_typing_progress("First one.");
+        assert!(app.awaiting_next_sentence());
+        assert_eq!(app.original_sentence, "First one.");
+        
+        app.update_typing_progress("x");
+        assert_eq!(app.typed_chars, 0);
+        
+        app.set_current_sentence("Second one.");
+        assert!(!app.awaiting_next_sentence());
+        assert_eq!(app.current_sentence, "Second one.");
+    }
+
+    #[wasm_bindgen_test]
+    fn manual_generation_or_skip_resumes_input() {
+        let mut app = app();
+        app.set_auto_generate(false);
+        start_on(&mut app, "First one.");
+        app.update_typing_progress("First one.");
+        assert!(app.awaiting_next_sentence());
+
+        let sentence = app.generate_new_sentence();
+        assert!(!app.awaiting_next_sentence());
+        let first: String = sentence.chars().take(1).collect();
+        app.update_typing_progress(&first);
+        assert_eq!(app.typed_chars, 1);
+
+        app.set_current_sentence("Third one.");
+        app.update_typing_progress("Third one.");
+        assert!(app.awaiting_next_sentence());
+        app.skip_sentence();
+        assert!(!app.awaiting_next_sentence());
+    }
+
+    #[wasm_bindgen_test]
+    fn characters_past_the_end_count_as_overtyped() {
+        let mut app = app();
+        app.set_strict_mode(true);
+        start_on(&mut app, "abcd");
+        let result: HashMap<String, f64> = serde_wasm_bindgen::from_value(app.update_typing_progress("aXcdYZ")).unwrap();
+        assert_eq!(result["overtyped_chars"], 2.0);
+        assert_eq!(result["typed_chars"], 6.0);
+        assert_eq!(result["correct_chars"], 3.0);
+    }
+
+    #[wasm_bindgen_test]
+    fn legacy_saved_duration_survives_a_language_change() {
+        let mut app = app();
+        let storage = window().unwrap().local_storage().unwrap().unwrap();
+        storage.set_item("typingAppUserData", r#"{"name":"Ayu","duration":60}"#).unwrap();
+        app.load_user_data();
+        assert!(app.user_data.duration_chosen);
+        app.set_language("merdeka", "Merdeka");
+        assert_eq!(app.user_data.duration, 60);
+        
+        storage.set_item("typingAppUserData", r#"{"name":"Ayu","duration":120}"#).unwrap();
+        app.load_user_data();
+        assert!(!app.user_data.duration_chosen);
+    }
+
+    #[wasm_bindgen_test]
+    fn selecting_a_language_applies_its_default_duration() {
+        let mut app = app();
+        app.set_language("merdeka", "Merdeka");
+        assert_eq!(app.user_data.duration, 180);
+        app.set_language("numbers", "Numbers");
+        assert_eq!(app.user_data.duration, 60);
+        
+        // Once the player picks a duration it sticks across languages
+        app.set_duration(150);
+        app.set_language("merdeka", "Merdeka");
+        assert_eq!(app.user_data.duration, 150);
+    }
+
+    #[wasm_bindgen_test]
+    fn completed_sentences_grow_without_duplicates() {
+        let mut app = app();
+        app.set_language("es", "Español");
+        app.user_data.sentences_per_round = 2;
+        app.start_game_session();
+        let round = app.original_sentence.clone();
+        type_sentence(&mut app, 0);
+        
+        // Each pool sentence of the round is recorded on its own, never the joined text
+        assert_eq!(app.completion_count(), 2);
+        assert!(!app.user_data.completed_sentences.contains(&round));
+        assert!(app.user_data.completed_sentences.iter().all(|text| app.sentences["es"].iter().any(|entry| entry.text == text)));
+        
+        let pool_size = app.sentences["es"].len();
+        for _ in 0..pool_size * 2 {
+            type_sentence(&mut app, 0);
         }
+        assert_eq!(app.completion_count() as usize, pool_size);
     }
 
-    // Getters for UI
-    #[wasm_bindgen(getter)]
-    pub fn user_name(&self) -> String {
-        self.user_data.name.clone()
+    #[wasm_bindgen_test]
+    fn practice_and_tutorial_sentences_are_not_recorded() {
+        let mut app = app();
+        app.start_practice();
+        type_sentence(&mut app, 0);
+        assert_eq!(app.completion_count(), 0);
+        
+        start_on(&mut app, "Tutorial text.");
+        type_sentence(&mut app, 0);
+        assert_eq!(app.completion_count(), 0);
+        type_sentence(&mut app, 0);
+        assert_eq!(app.completion_count(), 1);
     }
 
-    #[wasm_bindgen(getter)]
-    pub fn current_language(&self) -> String {
-        self.user_data.language.clone()
+    #[wasm_bindgen_test]
+    fn zen_ends_only_when_stopped() {
+        let mut app = app();
+        app.start_zen();
+        app.session_start_time = Some(Date::now() - 1000.0 * (app.user_data.duration as f64 + 60.0));
+        type_sentence(&mut app, 0);
+        type_sentence(&mut app, 0);
+        assert_eq!(app.app_state, AppState::Playing);
+        assert!(app.remaining_time().is_infinite());
+        
+        app.pause();
+        let calls = hook_calls();
+        app.resume();
+        let calls: Vec<String> = serde_wasm_bindgen::from_value(calls.into()).unwrap();
+        assert!(!calls.contains(&"startGameTimer".to_string()));
+        assert_eq!(app.app_state, AppState::Playing);
+        
+        app.stop_session();
+        assert_eq!(app.app_state, AppState::Results);
+        assert_eq!(app.session_result.as_ref().unwrap().sentences_completed, 2);
     }
 
-    #[wasm_bindgen(getter)]
-    pub fn current_sentence(&self) -> String {
-        self.current_sentence.clone()
+    #[wasm_bindgen_test]
+    fn identical_input_is_not_counted_twice() {
+        let mut app = app();
+        start_on(&mut app, "abcd");
+        app.update_typing_progress("ab");
+        let (keystrokes, samples) = (app.total_keystrokes, app.wpm_samples.len());
+        app.update_typing_progress("ab");
+        assert_eq!(app.total_keystrokes, keystrokes);
+        assert_eq!(app.wpm_samples.len(), samples);
+        assert_eq!(app.typed_chars, 2);
     }
 
-    #[wasm_bindgen(getter)]
-    pub fn session_wpm(&self) -> u32 {
-        self.calculate_session_wpm() as u32
+    #[wasm_bindgen_test]
+    fn identical_input_still_reports_expired_time() {
+        let mut app = app();
+        start_on(&mut app, "abcd");
+        app.update_typing_progress("ab");
+        app.session_start_time = Some(Date::now() - 1000.0 * (app.user_data.duration as f64 + 1.0));
+        let result: HashMap<String, f64> = serde_wasm_bindgen::from_value(app.update_typing_progress("ab")).unwrap();
+        assert_eq!(result["time_expired"], 1.0);
+        assert!(app.session_result.is_some());
     }
 
-    #[wasm_bindgen(getter)]
-    pub fn session_accuracy(&self) -> u32 {
-        self.calculate_session_accuracy() as u32
+    #[wasm_bindgen_test]
+    fn finishing_early_keeps_the_partial_sentence() {
+        let mut app = app();
+        assert!(!app.finish_session());
+        
+        app.start_game_session();
+        type_sentence(&mut app, 0);
+        let completed = app.session_total_typed_chars;
+        app.set_current_sentence("abcdefgh");
+        app.update_typing_progress("abcX");
+        
+        assert!(app.finish_session());
+        let result = app.session_result.as_ref().unwrap();
+        assert_eq!(result.typed_chars as usize, completed + 4);
+        assert_eq!(result.correct_chars as usize, completed + 3);
+        assert_eq!(result.sentences_completed, 1);
+        assert!(result.ended_incomplete);
     }
 
-    #[wasm_bindgen(getter)]
-    pub fn remaining_time(&self) -> f64 {
-        self.get_remaining_time()
+    #[wasm_bindgen_test]
+    fn remaining_sentences_counts_down_to_the_target() {
+        let mut app = app();
+        assert_eq!(app.remaining_sentences(), -1);
+        assert!(app.set_sentence_target(3));
+        app.start_game_session();
+        type_sentence(&mut app, 0);
+        assert_eq!(app.remaining_sentences(), 2);
+        type_sentence(&mut app, 0);
+        type_sentence(&mut app, 0);
+        assert_eq!(app.app_state, AppState::Results);
+        assert_eq!(app.remaining_sentences(), 0);
     }
-    
-    // Debug function to test Rust-JS connection
-    #[wasm_bindgen]
-    pub fn test_connection(&self) -> String {
-        console_log!("Rust function called successfully!");
-        format!("Rust is working! Current sentence: {}", self.current_sentence)
+
+    #[wasm_bindgen_test]
+    fn untimed_modes_ignore_the_sentence_target() {
+        let mut app = app();
+        app.set_sentence_target(3);
+        app.start_practice();
+        assert_eq!(app.remaining_sentences(), -1);
+        app.start_zen();
+        for _ in 0..4 {
+            type_sentence(&mut app, 0);
+        }
+        assert_eq!(app.remaining_sentences(), -1);
+        assert_eq!(app.app_state, AppState::Playing);
     }
-}
 
-#[wasm_bindgen(start)]
-pub fn main() {
-    console_log!("Rust TypingApp initialized!");
-}
\ No newline at end of file
+    #[wasm_bindgen_test]
+    fn mistake_counts_carry_over_to_the_next_session() {
+        let mut app = app();
+        start_on(&mut app, "abc");
+        app.update_typing_progress("x");
+        app.end_session();
+        app.initialize_game();
+        assert_eq!(app.user_data.mistake_counts.get(&'a'), Some(&1));
+
+        let reloaded = reload();
+        assert_eq!(reloaded.user_data.mistake_counts.get(&'a'), Some(&1));
+    }
+}